@@ -0,0 +1,142 @@
+use macroquad::prelude::*;
+
+/// One frame of a clip. `source` picks a region of a sprite sheet, `None`
+/// uses the whole texture; `scale` squashes or stretches the drawn sprite
+/// around its feet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimationFrame {
+    pub source: Option<Rect>,
+    pub duration: f32,
+    pub scale: Vec2,
+}
+
+impl AnimationFrame {
+    pub fn new(source: Option<Rect>, duration: f32) -> Self {
+        Self {
+            source,
+            duration,
+            scale: Vec2::ONE,
+        }
+    }
+
+    pub fn scaled(mut self, scale_x: f32, scale_y: f32) -> Self {
+        self.scale = Vec2::new(scale_x, scale_y);
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlaybackMode {
+    /// Starts over after the last frame.
+    Loop,
+    /// Holds the last frame once it has played.
+    OneShot,
+}
+
+#[derive(Clone, Debug)]
+pub struct Animation {
+    pub frames: Vec<AnimationFrame>,
+    pub mode: PlaybackMode,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<AnimationFrame>, mode: PlaybackMode) -> Self {
+        assert!(!frames.is_empty(), "an animation needs at least one frame");
+        Self { frames, mode }
+    }
+
+    pub fn total_duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.duration).sum()
+    }
+
+    pub fn is_finished(&self, elapsed: f32) -> bool {
+        self.mode == PlaybackMode::OneShot && elapsed >= self.total_duration()
+    }
+
+    /// The frame shown `elapsed` seconds after the clip started.
+    pub fn frame_at(&self, elapsed: f32) -> &AnimationFrame {
+        let total = self.total_duration();
+        let mut time = match self.mode {
+            PlaybackMode::Loop if total > 0.0 => elapsed.rem_euclid(total),
+            _ => elapsed,
+        };
+        for frame in &self.frames {
+            if time < frame.duration {
+                return frame;
+            }
+            time -= frame.duration;
+        }
+        self.frames.last().unwrap()
+    }
+}
+
+/// Plays one clip at a time, restarting the clock when the clip changes.
+#[derive(Clone, Debug)]
+pub struct AnimationPlayer<K: Copy + PartialEq> {
+    clip: K,
+    elapsed: f32,
+}
+
+impl<K: Copy + PartialEq> AnimationPlayer<K> {
+    pub fn new(clip: K) -> Self {
+        Self { clip, elapsed: 0.0 }
+    }
+
+    pub fn clip(&self) -> K {
+        self.clip
+    }
+
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn play(&mut self, clip: K) {
+        if clip != self.clip {
+            self.clip = clip;
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(mode: PlaybackMode) -> Animation {
+        Animation::new(
+            vec![
+                AnimationFrame::new(None, 0.1).scaled(1.0, 1.0),
+                AnimationFrame::new(None, 0.2).scaled(2.0, 2.0),
+            ],
+            mode,
+        )
+    }
+
+    #[test]
+    fn frames_last_their_duration_and_loops_wrap_while_one_shots_hold() {
+        let looping = clip(PlaybackMode::Loop);
+        assert_eq!(looping.frame_at(0.05).scale.x, 1.0);
+        assert_eq!(looping.frame_at(0.15).scale.x, 2.0);
+        assert_eq!(looping.frame_at(0.35).scale.x, 1.0);
+        assert!(!looping.is_finished(10.0));
+
+        let one_shot = clip(PlaybackMode::OneShot);
+        assert_eq!(one_shot.frame_at(0.35).scale.x, 2.0);
+        assert!(!one_shot.is_finished(0.25));
+        assert!(one_shot.is_finished(0.3));
+    }
+
+    #[test]
+    fn the_player_restarts_the_clock_only_on_a_new_clip() {
+        let mut player = AnimationPlayer::new(1);
+        player.advance(0.5);
+        player.play(1);
+        assert_eq!(player.elapsed(), 0.5);
+        player.play(2);
+        assert_eq!((player.clip(), player.elapsed()), (2, 0.0));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod animation_clip;
+pub mod obstacle_animations;
+pub mod particles;
+pub mod pickup_effects;
+pub mod player_animator;
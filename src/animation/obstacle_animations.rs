@@ -0,0 +1,39 @@
+use crate::types::obstacle_kind::Placement;
+use super::animation_clip::{Animation, AnimationFrame, PlaybackMode};
+
+/// Idle clips for obstacle textures. Obstacles have no state of their own
+/// to react to, so they are sampled from a shared clock.
+pub struct ObstacleAnimations {
+    grounded_idle: Animation,
+    floating_idle: Animation,
+}
+
+impl ObstacleAnimations {
+    pub fn new() -> Self {
+        Self {
+            grounded_idle: Animation::new(
+                vec![
+                    AnimationFrame::new(None, 0.4).scaled(1.0, 1.0),
+                    AnimationFrame::new(None, 0.4).scaled(1.02, 0.98),
+                ],
+                PlaybackMode::Loop,
+            ),
+            floating_idle: Animation::new(
+                vec![
+                    AnimationFrame::new(None, 0.3).scaled(1.0, 1.0),
+                    AnimationFrame::new(None, 0.3).scaled(0.97, 1.04),
+                    AnimationFrame::new(None, 0.3).scaled(1.0, 1.0),
+                    AnimationFrame::new(None, 0.3).scaled(1.03, 0.97),
+                ],
+                PlaybackMode::Loop,
+            ),
+        }
+    }
+
+    pub fn for_placement(&self, placement: Placement) -> &Animation {
+        match placement {
+            Placement::Floating => &self.floating_idle,
+            Placement::Ground | Placement::Ceiling => &self.grounded_idle,
+        }
+    }
+}
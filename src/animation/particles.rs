@@ -0,0 +1,185 @@
+use macroquad::prelude::*;
+use crate::simulation::{rng::SeededRng, sim_event::SimEvent, world::World};
+use crate::tuning::ParticleTuning;
+use crate::types::particle_emitter::ParticleEffect;
+
+// Particles only decorate, so a fixed seed is enough.
+const PARTICLE_SEED: u64 = 0x5007;
+
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    age: f32,
+    lifetime: f32,
+    effect: ParticleEffect,
+}
+
+/// A fixed-size pool of particles driven by the emitters in the tuning.
+/// The pool is allocated once and a dead particle is swapped out with the
+/// last one, so thousands can run without allocating per frame.
+pub struct Particles {
+    pool: Vec<Particle>,
+    /// Fractions of a particle each running effect still owes.
+    carry: [f32; ParticleEffect::COUNT],
+    rng: SeededRng,
+}
+
+impl Particles {
+    pub fn new(tuning: &ParticleTuning) -> Self {
+        Self {
+            pool: Vec::with_capacity(tuning.max_particles),
+            carry: [0.0; ParticleEffect::COUNT],
+            rng: SeededRng::new(PARTICLE_SEED),
+        }
+    }
+
+    /// Spawns the effect's whole burst at `at`.
+    pub fn burst(&mut self, effect: ParticleEffect, tuning: &ParticleTuning, at: Vec2) {
+        self.spawn(effect, tuning, at, tuning.emitter(effect).burst);
+    }
+
+    /// Spawns the effect's rate worth of particles for `dt` at `at`.
+    pub fn emit(&mut self, effect: ParticleEffect, tuning: &ParticleTuning, at: Vec2, dt: f32) {
+        let carry = &mut self.carry[effect.index()];
+        *carry += tuning.emitter(effect).rate * dt;
+        let count = carry.floor();
+        *carry -= count;
+        self.spawn(effect, tuning, at, count as u32);
+    }
+
+    fn spawn(&mut self, effect: ParticleEffect, tuning: &ParticleTuning, at: Vec2, count: u32) {
+        let emitter = tuning.emitter(effect);
+        let free = tuning.max_particles.saturating_sub(self.pool.len());
+        for _ in 0..(count as usize).min(free) {
+            let half_spread = emitter.spread_deg / 2.0;
+            let angle = (emitter.direction_deg + self.rng.gen_range_f32(-half_spread, half_spread)).to_radians();
+            let speed = self.rng.gen_range_f32(emitter.speed[0], emitter.speed[1]);
+            // The square root spreads spawns evenly over the disc.
+            let spawn_angle = self.rng.gen_range_f32(0.0, std::f32::consts::TAU);
+            let spawn_distance = emitter.spawn_radius * self.rng.gen_range_f32(0.0, 1.0).sqrt();
+            self.pool.push(Particle {
+                position: at + Vec2::from(emitter.offset) + Vec2::from_angle(spawn_angle) * spawn_distance,
+                // Screen y points down.
+                velocity: Vec2::new(angle.cos(), -angle.sin()) * speed,
+                age: 0.0,
+                lifetime: self.rng.gen_range_f32(emitter.lifetime_s[0], emitter.lifetime_s[1]),
+                effect,
+            });
+        }
+    }
+
+    /// Ages and moves every particle, carrying each left by its emitter's
+    /// share of `scroll_speed`.
+    pub fn update(&mut self, dt: f32, tuning: &ParticleTuning, scroll_speed: f32) {
+        let mut index = 0;
+        while index < self.pool.len() {
+            let particle = &mut self.pool[index];
+            particle.age += dt;
+            if particle.age >= particle.lifetime {
+                self.pool.swap_remove(index);
+                continue;
+            }
+            let emitter = tuning.emitter(particle.effect);
+            particle.velocity.y += emitter.gravity * dt;
+            particle.position += particle.velocity * dt;
+            particle.position.x -= scroll_speed * emitter.scroll_factor * dt;
+            index += 1;
+        }
+    }
+
+    /// Draws every particle as `texture` centred on it, sized and tinted
+    /// from its emitter's curves.
+    pub fn draw(&self, tuning: &ParticleTuning, texture: &Texture2D) {
+        for particle in &self.pool {
+            let emitter = tuning.emitter(particle.effect);
+            let life = particle.age / particle.lifetime;
+            let size = emitter.size_at(life);
+            draw_texture_ex(
+                texture,
+                particle.position.x - size / 2.0,
+                particle.position.y - size / 2.0,
+                Color::from(emitter.color_at(life)),
+                DrawTextureParams {
+                    dest_size: Some(Vec2::splat(size)),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+/// Plays a run's particle effects from what its `World` does: dust on
+/// landing, a soot trail while running, a burst on collisions and
+/// sparkles on milestones.
+pub struct RunParticles {
+    particles: Particles,
+    was_on_floor: bool,
+}
+
+impl RunParticles {
+    pub fn new(world: &World) -> Self {
+        Self {
+            particles: Particles::new(&world.tuning().particles),
+            was_on_floor: world.is_on_floor,
+        }
+    }
+
+    pub fn on_event(&mut self, event: SimEvent, world: &World) {
+        let effect = match event {
+            SimEvent::Collided | SimEvent::CollisionAbsorbed => ParticleEffect::CollisionBurst,
+            SimEvent::MilestoneReached(_) => ParticleEffect::MilestoneSparkles,
+            _ => return,
+        };
+        self.particles.burst(effect, &world.tuning().particles, world.player_hitbox().center);
+    }
+
+    /// `dt` is run time, so a replay played slower or faster moves its
+    /// particles to match.
+    pub fn update(&mut self, dt: f32, world: &World) {
+        let tuning = &world.tuning().particles;
+        let feet = Vec2::new(world.tuning().player.x_position, world.player_y_position);
+        if world.is_on_floor && !self.was_on_floor {
+            self.particles.burst(ParticleEffect::LandingDust, tuning, feet);
+        }
+        self.was_on_floor = world.is_on_floor;
+        if world.is_on_floor && !world.is_over {
+            self.particles.emit(ParticleEffect::SootTrail, tuning, feet, dt);
+        }
+        let scroll_speed = if world.is_over { 0.0 } else { world.scroll_speed };
+        self.particles.update(dt, tuning, scroll_speed);
+    }
+
+    pub fn draw(&self, world: &World, texture: &Texture2D) {
+        self.particles.draw(&world.tuning().particles, texture);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_pool_is_capped_and_recycles_expired_particles() {
+        let tuning = ParticleTuning {
+            max_particles: 100,
+            ..ParticleTuning::default()
+        };
+        let mut particles = Particles::new(&tuning);
+        for _ in 0..10 {
+            particles.burst(ParticleEffect::CollisionBurst, &tuning, Vec2::ZERO);
+        }
+        assert_eq!(particles.pool.len(), 100);
+        particles.emit(ParticleEffect::SootTrail, &tuning, Vec2::ZERO, 1.0);
+        assert_eq!(particles.pool.len(), 100);
+
+        particles.update(tuning.collision_burst.lifetime_s[1], &tuning, 0.0);
+        assert_eq!(particles.pool.len(), 0);
+        assert_eq!(particles.pool.capacity(), 100);
+
+        // 40 per second, with the fraction carried over between frames.
+        for _ in 0..4 {
+            particles.emit(ParticleEffect::SootTrail, &tuning, Vec2::ZERO, 0.03);
+        }
+        assert_eq!(particles.pool.len(), 4);
+    }
+}
@@ -0,0 +1,47 @@
+use macroquad::prelude::*;
+use crate::assets::Assets;
+use crate::config;
+use crate::game_stages::world_renderer;
+
+struct PickupEffect {
+    center: Vec2,
+    age: f32,
+}
+
+/// Short bursts where stars were collected. They only decorate, so they
+/// live in the stage rather than in the simulation.
+pub struct PickupEffects {
+    effects: Vec<PickupEffect>,
+}
+
+impl PickupEffects {
+    pub fn new() -> Self {
+        Self { effects: Vec::new() }
+    }
+
+    pub fn spawn(&mut self, center: Vec2) {
+        self.effects.push(PickupEffect { center, age: 0.0 });
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for effect in &mut self.effects {
+            effect.age += dt;
+        }
+        self.effects.retain(|effect| effect.age < config::stars::PICKUP_DURATION_S);
+    }
+
+    /// The star grows, rises and fades inside a widening ring.
+    pub fn draw(&self, assets: &Assets, star_size: f32) {
+        for effect in &self.effects {
+            let progress = effect.age / config::stars::PICKUP_DURATION_S;
+            let fade = 1.0 - progress;
+            let center = effect.center - Vec2::new(0.0, config::stars::PICKUP_RISE * progress);
+            let size = star_size * (1.0 + config::stars::PICKUP_GROWTH * progress);
+            world_renderer::draw_star(assets, center, size, Color::new(1.0, 1.0, 1.0, fade));
+
+            let mut ring_color = config::stars::PICKUP_RING_COLOR;
+            ring_color.a *= fade;
+            draw_circle_lines(effect.center.x, effect.center.y, size * 0.8, 3.0, ring_color);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use super::animation_clip::{Animation, AnimationFrame, AnimationPlayer, PlaybackMode};
+
+/// The clips the soot sprite can be in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlayerClip {
+    Run,
+    JumpRise,
+    Fall,
+    LandSquash,
+    Death,
+}
+
+/// Picks the player's clip from the simulation state each frame. The art
+/// is a single sprite, so the clips squash and stretch it; a sprite sheet
+/// only needs `source` rects on the frames.
+pub struct PlayerAnimator {
+    run: Animation,
+    jump_rise: Animation,
+    fall: Animation,
+    land_squash: Animation,
+    death: Animation,
+    player: AnimationPlayer<PlayerClip>,
+    was_on_floor: bool,
+}
+
+impl PlayerAnimator {
+    pub fn new() -> Self {
+        Self {
+            run: Animation::new(
+                vec![
+                    AnimationFrame::new(None, 0.12).scaled(1.0, 1.0),
+                    AnimationFrame::new(None, 0.12).scaled(1.04, 0.95),
+                    AnimationFrame::new(None, 0.12).scaled(1.0, 1.0),
+                    AnimationFrame::new(None, 0.12).scaled(0.97, 1.03),
+                ],
+                PlaybackMode::Loop,
+            ),
+            jump_rise: Animation::new(
+                vec![AnimationFrame::new(None, 1.0).scaled(0.88, 1.14)],
+                PlaybackMode::Loop,
+            ),
+            fall: Animation::new(
+                vec![AnimationFrame::new(None, 1.0).scaled(0.95, 1.06)],
+                PlaybackMode::Loop,
+            ),
+            land_squash: Animation::new(
+                vec![
+                    AnimationFrame::new(None, 0.05).scaled(1.2, 0.78),
+                    AnimationFrame::new(None, 0.06).scaled(1.1, 0.9),
+                    AnimationFrame::new(None, 0.05).scaled(1.0, 1.0),
+                ],
+                PlaybackMode::OneShot,
+            ),
+            death: Animation::new(
+                vec![
+                    AnimationFrame::new(None, 0.08).scaled(1.25, 0.75),
+                    AnimationFrame::new(None, 0.12).scaled(1.45, 0.45),
+                    AnimationFrame::new(None, 0.3).scaled(1.6, 0.25),
+                ],
+                PlaybackMode::OneShot,
+            ),
+            player: AnimationPlayer::new(PlayerClip::Run),
+            was_on_floor: true,
+        }
+    }
+
+    fn animation(&self, clip: PlayerClip) -> &Animation {
+        match clip {
+            PlayerClip::Run => &self.run,
+            PlayerClip::JumpRise => &self.jump_rise,
+            PlayerClip::Fall => &self.fall,
+            PlayerClip::LandSquash => &self.land_squash,
+            PlayerClip::Death => &self.death,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32, is_on_floor: bool, player_velocity_y: f32, is_dead: bool) {
+        let current = self.player.clip();
+        let current_finished = self.animation(current).is_finished(self.player.elapsed());
+        let next = if is_dead {
+            PlayerClip::Death
+        } else if !is_on_floor {
+            if player_velocity_y < 0.0 {
+                PlayerClip::JumpRise
+            } else {
+                PlayerClip::Fall
+            }
+        } else if !self.was_on_floor
+            || (current == PlayerClip::LandSquash && !current_finished) {
+            PlayerClip::LandSquash
+        } else {
+            PlayerClip::Run
+        };
+        self.was_on_floor = is_on_floor;
+        self.player.play(next);
+        self.player.advance(dt);
+    }
+
+    pub fn current_frame(&self) -> &AnimationFrame {
+        self.animation(self.player.clip()).frame_at(self.player.elapsed())
+    }
+
+    /// True once a one-shot clip such as `Death` has shown its last frame.
+    pub fn is_finished(&self) -> bool {
+        self.animation(self.player.clip()).is_finished(self.player.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn clips_follow_jumping_falling_landing_and_death() {
+        let mut animator = PlayerAnimator::new();
+        animator.update(DT, true, 0.0, false);
+        assert_eq!(animator.player.clip(), PlayerClip::Run);
+
+        animator.update(DT, false, -800.0, false);
+        assert_eq!(animator.player.clip(), PlayerClip::JumpRise);
+        animator.update(DT, false, 300.0, false);
+        assert_eq!(animator.player.clip(), PlayerClip::Fall);
+
+        // The squash plays out on the floor before running again.
+        animator.update(DT, true, 0.0, false);
+        assert_eq!(animator.player.clip(), PlayerClip::LandSquash);
+        animator.update(DT, true, 0.0, false);
+        assert_eq!(animator.player.clip(), PlayerClip::LandSquash);
+        for _ in 0..10 {
+            animator.update(DT, true, 0.0, false);
+        }
+        assert_eq!(animator.player.clip(), PlayerClip::Run);
+
+        animator.update(DT, true, 0.0, true);
+        assert_eq!(animator.player.clip(), PlayerClip::Death);
+        assert!(!animator.is_finished());
+        for _ in 0..60 {
+            animator.update(DT, true, 0.0, true);
+        }
+        assert!(animator.is_finished());
+        assert_eq!(animator.current_frame().scale, macroquad::math::Vec2::new(1.6, 0.25));
+    }
+}
@@ -1,32 +1,89 @@
 use macroquad::prelude::*;
+use crate::animation::obstacle_animations::ObstacleAnimations;
+use crate::audio::Sounds;
+use crate::config;
+use crate::tuning::Tuning;
 
 pub struct Assets {
-    pub background: Texture2D,
+    /// One texture per background layer, in tuning order.
+    pub background_layers: Vec<Texture2D>,
+    pub ground: Texture2D,
     pub player: Texture2D,
-    pub object0: Texture2D,
-    pub object1: Texture2D,
-    pub object2: Texture2D,
+    pub star: Texture2D,
+    /// A soft white dot, tinted per particle.
+    pub particle: Texture2D,
+    /// One texture per obstacle kind, in catalogue order.
+    pub obstacle_textures: Vec<Texture2D>,
     pub bold_font: Font,
+    pub sounds: Sounds,
+    pub obstacle_animations: ObstacleAnimations,
 }
 
+const PLAYER_PATH: &str = "assets/player.png";
+const STAR_PATH: &str = "assets/star.PNG";
+
 impl Assets {
-    pub async fn load() -> Result<Self, macroquad::Error> {
-        let background = load_texture("assets/background.png").await?;
-        let player = load_texture("assets/player.png").await?;
-        let object0 = load_texture("assets/object_0.png").await?;
-        let object1 = load_texture("assets/object_1.png").await?;
-        let object2 = load_texture("assets/object_2.png").await?;
+    pub async fn load(tuning: &Tuning) -> Result<Self, macroquad::Error> {
+        let background_layers = load_textures(tuning.background.layers.iter().map(|layer| &layer.texture)).await?;
+        let ground = load_texture(&tuning.background.ground.texture).await?;
+        let player = load_texture(PLAYER_PATH).await?;
+        let star = load_texture(STAR_PATH).await?;
+        let obstacle_textures = load_textures(tuning.obstacles.kinds.iter().map(|kind| &kind.texture)).await?;
 
         let bold_font = load_ttf_font("raw/bold.ttf").await?;
+        let sounds = Sounds::load().await?;
         Ok(
             Self {
-                background,
+                background_layers,
+                ground,
                 player,
-                object0,
-                object1,
-                object2,
+                star,
+                particle: soft_dot_texture(),
+                obstacle_textures,
                 bold_font,
+                sounds,
+                obstacle_animations: ObstacleAnimations::new(),
             }
         )
     }
-}
\ No newline at end of file
+
+    /// Loads every texture again and swaps them in only if all of them
+    /// loaded, so a half-saved PNG leaves the old set in place.
+    pub async fn reload_textures(&mut self, tuning: &Tuning) -> Result<(), macroquad::Error> {
+        let background_layers = load_textures(tuning.background.layers.iter().map(|layer| &layer.texture)).await?;
+        let ground = load_texture(&tuning.background.ground.texture).await?;
+        let player = load_texture(PLAYER_PATH).await?;
+        let star = load_texture(STAR_PATH).await?;
+        let obstacle_textures = load_textures(tuning.obstacles.kinds.iter().map(|kind| &kind.texture)).await?;
+
+        self.background_layers = background_layers;
+        self.ground = ground;
+        self.player = player;
+        self.star = star;
+        self.obstacle_textures = obstacle_textures;
+        Ok(())
+    }
+}
+
+/// A white dot fading out towards its edge, generated rather than loaded.
+fn soft_dot_texture() -> Texture2D {
+    let size = config::particles::TEXTURE_SIZE;
+    let mut image = Image::gen_image_color(size, size, WHITE);
+    let radius = size as f32 / 2.0;
+    for y in 0..size {
+        for x in 0..size {
+            let offset = Vec2::new(x as f32 + 0.5, y as f32 + 0.5) - Vec2::splat(radius);
+            let alpha = (1.0 - offset.length() / radius).clamp(0.0, 1.0);
+            image.set_pixel(x as u32, y as u32, Color::new(1.0, 1.0, 1.0, alpha.sqrt()));
+        }
+    }
+    Texture2D::from_image(&image)
+}
+
+async fn load_textures(paths: impl Iterator<Item = &String>) -> Result<Vec<Texture2D>, macroquad::Error> {
+    let mut textures = Vec::new();
+    for path in paths {
+        textures.push(load_texture(path).await?);
+    }
+    Ok(textures)
+}
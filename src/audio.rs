@@ -0,0 +1,134 @@
+use macroquad::audio::{load_sound, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound};
+use crate::simulation::sim_event::SimEvent;
+use crate::state::settings::AudioSettings;
+
+/// One-shot sound effects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SoundCue {
+    Jump,
+    Collision,
+    Milestone,
+}
+
+impl SoundCue {
+    pub fn for_event(event: SimEvent) -> Option<Self> {
+        match event {
+            SimEvent::Jumped => Some(SoundCue::Jump),
+            SimEvent::Collided | SimEvent::CollisionAbsorbed => Some(SoundCue::Collision),
+            SimEvent::MilestoneReached(_) | SimEvent::PowerUpCollected(_) => Some(SoundCue::Milestone),
+            SimEvent::Scored(_) | SimEvent::StarCollected | SimEvent::PowerUpEnded(_) => None,
+        }
+    }
+}
+
+/// Looping background music, one per kind of stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MusicTrack {
+    Intro,
+    Playing,
+    GameOver,
+}
+
+#[derive(Clone)]
+pub struct Sounds {
+    pub jump: Sound,
+    pub collision: Sound,
+    pub milestone: Sound,
+    pub music_intro: Sound,
+    pub music_playing: Sound,
+    pub music_game_over: Sound,
+}
+
+impl Sounds {
+    pub async fn load() -> Result<Self, macroquad::Error> {
+        Ok(
+            Self {
+                jump: load_sound("assets/audio/jump.wav").await?,
+                collision: load_sound("assets/audio/collision.wav").await?,
+                milestone: load_sound("assets/audio/milestone.wav").await?,
+                music_intro: load_sound("assets/audio/music_intro.wav").await?,
+                music_playing: load_sound("assets/audio/music_playing.wav").await?,
+                music_game_over: load_sound("assets/audio/music_game_over.wav").await?,
+            }
+        )
+    }
+
+    fn cue(&self, cue: SoundCue) -> &Sound {
+        match cue {
+            SoundCue::Jump => &self.jump,
+            SoundCue::Collision => &self.collision,
+            SoundCue::Milestone => &self.milestone,
+        }
+    }
+
+    fn music(&self, track: MusicTrack) -> &Sound {
+        match track {
+            MusicTrack::Intro => &self.music_intro,
+            MusicTrack::Playing => &self.music_playing,
+            MusicTrack::GameOver => &self.music_game_over,
+        }
+    }
+}
+
+/// Plays cues and keeps the right music track looping at the volumes
+/// from the player's settings.
+pub struct AudioPlayer {
+    sounds: Sounds,
+    settings: AudioSettings,
+    current_music: Option<MusicTrack>,
+}
+
+impl AudioPlayer {
+    pub fn new(sounds: Sounds, settings: AudioSettings) -> Self {
+        Self {
+            sounds,
+            settings,
+            current_music: None,
+        }
+    }
+
+    pub fn play(&self, cue: SoundCue) {
+        let volume = self.settings.sfx_level();
+        if volume > 0.0 {
+            play_sound(
+                self.sounds.cue(cue),
+                PlaySoundParams {
+                    looped: false,
+                    volume,
+                },
+            );
+        }
+    }
+
+    /// Switches the looping music. Asking for the track that is already
+    /// playing keeps it going without restarting it.
+    pub fn play_music(&mut self, track: Option<MusicTrack>) {
+        if track == self.current_music {
+            return;
+        }
+        if let Some(previous) = self.current_music {
+            stop_sound(self.sounds.music(previous));
+        }
+        if let Some(track) = track {
+            play_sound(
+                self.sounds.music(track),
+                PlaySoundParams {
+                    looped: true,
+                    volume: self.settings.music_level(),
+                },
+            );
+        }
+        self.current_music = track;
+    }
+
+    /// Applies changed volume settings, including to the music already playing.
+    pub fn set_settings(&mut self, settings: AudioSettings) {
+        if settings == self.settings {
+            return;
+        }
+        self.settings = settings;
+        if let Some(track) = self.current_music {
+            set_sound_volume(self.sounds.music(track), self.settings.music_level());
+        }
+    }
+}
@@ -1,42 +1,139 @@
+// Simulation timing
+pub const FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+pub const MAX_FRAME_TIME: f32 = 0.25;
+pub const SCORE_INTERVAL_S: f32 = 0.1;
 
-// General Gameplay
-pub const PLAYER_X_POSITION: f32 = 75.0;
-pub const GRAVITY: f32 = 1.5;
-pub const JUMP_FORCE: f32 = -25.0;
-pub const PLAYER_RADIUS: f32 = 40.0;
-
-// Obstacles
-pub const OBSTACLE_DEFAULT_WIDTH: f32 = 80.0;
-pub const OBSTACLE_DEFAULT_HEIGHT: f32 = 60.0;
-pub const OBJECT1_OWN_WIDTH: f32 = 70.0;
-pub const OBJECT1_OWN_HEIGHT: f32 = 100.0;
-pub const OBJECT1_ADDITIONAL_CLEARANCE_ABOVE_OTHERS: f32 = 30.0;
-pub const OBSTACLE_SPAWN_TIMER_MIN: f32 = 1.5;
-pub const OBSTACLE_SPAWN_TIMER_MAX: f32 = 3.0;
-
-// Scroll Speed Tiers based on ScoreType values
-pub const SCROLL_SPEED_INITIAL: f32 = 300.0;
-pub const SCROLL_SPEED_TIER1: f32 = 400.0;
-pub const SCROLL_SPEED_TIER2: f32 = 500.0;
-pub const SCROLL_SPEED_TIER3: f32 = 600.0;
-pub const SCROLL_SPEED_TIER4: f32 = 700.0;
-pub const SCROLL_SPEED_TIER5: f32 = 800.0;
-
-// UI and Intro
-pub mod intro {
-    pub const SLIDE_DURATION_S: f32 = 8.0;
-    pub const BUTTON_WIDTH: f32 = 200.0;
-    pub const BUTTON_HEIGHT: f32 = 50.0;
-    pub const FONT_SIZE: f32 = 30.0;
-    pub const LINE_HEIGHT_FACTOR: f32 = 1.3;
-}
-
-pub mod playing_ui {
-    pub const SCORE_FONT_SIZE: f32 = 30.0;
-    pub const SCORE_MARGIN: f32 = 20.0;
-}
-
-pub mod game_over_ui {
-    pub const FONT_SIZE: f32 = 60.0;
-    pub const SCORE_FONT_SIZE: f32 = 30.0;
-}
\ No newline at end of file
+// Pixels the spawner waits before trying again when the fairness check
+// rejected a layout.
+pub const SPAWN_RETRY_DISTANCE: f32 = 40.0;
+
+// Horizontal bands an `alpha_mask` obstacle hitbox is traced into.
+pub const ALPHA_MASK_BANDS: usize = 4;
+
+// Gameplay values and the intro, playing and game over UI sizes are
+// loaded from `tuning::TUNING_PATH`.
+
+pub mod display {
+    use macroquad::color::Color;
+
+    /// Every stage is laid out for this size, whatever the window.
+    pub const VIRTUAL_WIDTH: f32 = 800.0;
+    pub const VIRTUAL_HEIGHT: f32 = 600.0;
+    pub const LETTERBOX_COLOR: Color = Color::new(0.0, 0.0, 0.0, 1.0);
+}
+
+pub mod replay_ui {
+    pub const SPEEDS: [f32; 3] = [0.5, 1.0, 2.0];
+    pub const DEFAULT_SPEED_INDEX: usize = 1;
+    pub const CONTROLS_FONT_SIZE: f32 = 20.0;
+}
+
+pub mod ghost {
+    use macroquad::color::Color;
+
+    pub const TINT: Color = Color::new(1.0, 1.0, 1.0, 0.35);
+    /// The ghost is drawn this far left of the player so it is not hidden
+    /// behind the player's sprite.
+    pub const X_OFFSET: f32 = -40.0;
+    pub const AHEAD_COLOR: Color = Color::new(0.1, 0.55, 0.2, 1.0);
+    pub const BEHIND_COLOR: Color = Color::new(0.7, 0.15, 0.15, 1.0);
+    pub const EVEN_COLOR: Color = Color::new(0.3, 0.3, 0.3, 1.0);
+    pub const LEAD_FONT_SIZE: f32 = 20.0;
+}
+
+pub mod stars {
+    use macroquad::color::Color;
+
+    pub const BOB_SPEED: f32 = 4.0;
+    pub const BOB_HEIGHT: f32 = 3.0;
+    pub const HUD_ICON_SIZE: f32 = 26.0;
+    pub const PICKUP_DURATION_S: f32 = 0.35;
+    /// Extra scale the star grows by over the pickup effect.
+    pub const PICKUP_GROWTH: f32 = 0.8;
+    pub const PICKUP_RISE: f32 = 30.0;
+    pub const PICKUP_RING_COLOR: Color = Color::new(1.0, 0.85, 0.2, 1.0);
+}
+
+pub mod particles {
+    /// Width and height of the generated particle texture.
+    pub const TEXTURE_SIZE: u16 = 32;
+}
+
+pub mod power_ups {
+    use macroquad::color::Color;
+
+    pub const SHIELD_COLOR: Color = Color::new(0.2, 0.6, 1.0, 1.0);
+    pub const DOUBLE_JUMP_COLOR: Color = Color::new(0.3, 0.8, 0.3, 1.0);
+    pub const SLOW_MOTION_COLOR: Color = Color::new(0.6, 0.35, 0.9, 1.0);
+    pub const MAGNET_COLOR: Color = Color::new(0.9, 0.25, 0.25, 1.0);
+    pub const SCORE_MULTIPLIER_COLOR: Color = Color::new(1.0, 0.7, 0.1, 1.0);
+    pub const SHIELD_BUBBLE_ALPHA: f32 = 0.25;
+    pub const HUD_BAR_WIDTH: f32 = 120.0;
+    pub const HUD_BAR_HEIGHT: f32 = 8.0;
+    pub const HUD_ROW_HEIGHT: f32 = 24.0;
+    pub const HUD_FONT_SIZE: f32 = 16.0;
+}
+
+pub mod menu_ui {
+    use macroquad::color::Color;
+
+    pub const ITEM_WIDTH: f32 = 340.0;
+    pub const ITEM_HEIGHT: f32 = 44.0;
+    pub const ITEM_SPACING: f32 = 12.0;
+    pub const FONT_SIZE: f32 = 26.0;
+    pub const TITLE_FONT_SIZE: f32 = 48.0;
+    pub const OVERLAY_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.45);
+    pub const ITEM_COLOR: Color = Color::new(0.15, 0.15, 0.15, 1.0);
+    pub const SELECTED_ITEM_COLOR: Color = Color::new(0.4, 0.4, 0.4, 1.0);
+}
+
+pub mod pause {
+    /// A frame this long is taken to mean the window was hidden or
+    /// unfocused. This is only a heuristic: macroquad 0.4 does not pass
+    /// miniquad's minimize and focus events on, so a loading hitch with the
+    /// window focused also pauses, and a shorter alt-tab does not.
+    pub const FOCUS_LOSS_FRAME_TIME: f32 = 0.5;
+}
+
+pub mod settings_ui {
+    pub const VOLUME_STEP: f32 = 0.1;
+}
+
+pub mod dev {
+    pub const ASSETS_DIR: &str = "assets";
+    pub const POLL_INTERVAL_S: f32 = 0.5;
+}
+
+pub mod toast {
+    use macroquad::color::Color;
+
+    pub const DURATION_S: f32 = 2.5;
+    pub const ERROR_DURATION_S: f32 = 8.0;
+    pub const FADE_S: f32 = 0.5;
+    pub const FONT_SIZE: f32 = 20.0;
+    pub const MARGIN: f32 = 12.0;
+    pub const SUCCESS_COLOR: Color = Color::new(0.1, 0.45, 0.2, 0.85);
+    pub const ERROR_COLOR: Color = Color::new(0.6, 0.1, 0.1, 0.9);
+}
+
+pub mod debug {
+    use macroquad::color::Color;
+    use macroquad::input::KeyCode;
+
+    pub const HITBOX_COLOR: Color = Color::new(1.0, 0.1, 0.4, 0.9);
+    pub const HITBOX_THICKNESS: f32 = 2.0;
+
+    pub const OVERLAY_TOGGLE_KEY: KeyCode = KeyCode::F3;
+    pub const RECT_COLOR: Color = Color::new(0.1, 0.4, 1.0, 0.8);
+    pub const FLOOR_COLOR: Color = Color::new(0.0, 0.8, 0.3, 1.0);
+    pub const FONT_SIZE: f32 = 18.0;
+    pub const PANEL_Y: f32 = 100.0;
+    pub const PANEL_MARGIN: f32 = 8.0;
+    pub const PANEL_COLOR: Color = Color::new(0.0, 0.0, 0.0, 0.7);
+    pub const FRAME_GRAPH_SAMPLES: usize = 120;
+    pub const GRAPH_WIDTH: f32 = 240.0;
+    pub const GRAPH_HEIGHT: f32 = 50.0;
+    pub const GRAPH_BACKGROUND: Color = Color::new(0.2, 0.2, 0.2, 1.0);
+    pub const FRAME_COLOR: Color = Color::new(0.3, 0.9, 0.4, 1.0);
+    pub const SLOW_FRAME_COLOR: Color = Color::new(1.0, 0.5, 0.1, 1.0);
+}
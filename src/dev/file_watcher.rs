@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::config;
+
+/// What changed on disk since the last poll.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FileChanges {
+    pub tuning: bool,
+    /// Every other file under the assets directory that changed.
+    pub assets: Vec<PathBuf>,
+}
+
+/// Polls modification times under the assets directory. Polling keeps it
+/// dependency free, and a dev build only needs to notice saves within a
+/// fraction of a second.
+pub struct FileWatcher {
+    tuning_path: PathBuf,
+    assets_dir: PathBuf,
+    modified_times: HashMap<PathBuf, SystemTime>,
+    poll_timer: f32,
+}
+
+impl FileWatcher {
+    pub fn new(tuning_path: &Path, assets_dir: &Path) -> Self {
+        let mut watcher = Self {
+            tuning_path: tuning_path.to_path_buf(),
+            assets_dir: assets_dir.to_path_buf(),
+            modified_times: HashMap::new(),
+            poll_timer: 0.0,
+        };
+        watcher.modified_times = watcher.scan();
+        watcher
+    }
+
+    /// Returns the files that were added, removed or rewritten since the
+    /// previous scan, checking the disk at most every `POLL_INTERVAL_S`.
+    pub fn poll(&mut self, dt: f32) -> FileChanges {
+        let mut changes = FileChanges::default();
+        self.poll_timer += dt;
+        if self.poll_timer < config::dev::POLL_INTERVAL_S {
+            return changes;
+        }
+        self.poll_timer = 0.0;
+
+        let modified_times = self.scan();
+        let changed_paths = modified_times
+            .iter()
+            .filter(|&(path, time)| self.modified_times.get(path) != Some(time))
+            .map(|(path, _)| path)
+            .chain(
+                self.modified_times
+                    .keys()
+                    .filter(|path| !modified_times.contains_key(*path))
+            );
+        for path in changed_paths {
+            if *path == self.tuning_path {
+                changes.tuning = true;
+            } else {
+                changes.assets.push(path.clone());
+            }
+        }
+        self.modified_times = modified_times;
+        changes
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut modified_times = HashMap::new();
+        let mut pending_dirs = vec![self.assets_dir.clone()];
+        while let Some(dir) = pending_dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if metadata.is_dir() {
+                    pending_dirs.push(path);
+                } else if let Ok(modified) = metadata.modified() {
+                    modified_times.insert(path, modified);
+                }
+            }
+        }
+        // The tuning file may live outside the assets directory.
+        if let Ok(modified) = std::fs::metadata(&self.tuning_path).and_then(|m| m.modified()) {
+            modified_times.insert(self.tuning_path.clone(), modified);
+        }
+        modified_times
+    }
+}
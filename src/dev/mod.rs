@@ -0,0 +1,2 @@
+pub mod file_watcher;
+pub mod toast;
@@ -0,0 +1,71 @@
+use macroquad::prelude::*;
+use crate::config;
+
+/// A short message in the bottom-left corner that fades out on its own.
+pub struct Toast {
+    message: String,
+    is_error: bool,
+    remaining: f32,
+}
+
+impl Toast {
+    pub fn new() -> Self {
+        Self {
+            message: String::new(),
+            is_error: false,
+            remaining: 0.0,
+        }
+    }
+
+    pub fn show(&mut self, message: String) {
+        self.message = message;
+        self.is_error = false;
+        self.remaining = config::toast::DURATION_S;
+    }
+
+    /// Errors stay up longer so a multi-line parse error can be read.
+    pub fn show_error(&mut self, message: String) {
+        self.message = message;
+        self.is_error = true;
+        self.remaining = config::toast::ERROR_DURATION_S;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.remaining = (self.remaining - dt).max(0.0);
+    }
+
+    pub fn draw(&self, screen_height: f32) {
+        if self.remaining <= 0.0 {
+            return;
+        }
+        let fade = (self.remaining / config::toast::FADE_S).min(1.0);
+        let font_size = config::toast::FONT_SIZE;
+        let line_height = font_size * 1.2;
+        let lines: Vec<&str> = self.message.lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| measure_text(line, None, font_size as u16, 1.0).width)
+            .fold(0.0, f32::max);
+        let height = lines.len() as f32 * line_height;
+        let margin = config::toast::MARGIN;
+        let x = margin;
+        let y = screen_height - margin - height - margin;
+
+        let mut background = if self.is_error {
+            config::toast::ERROR_COLOR
+        } else {
+            config::toast::SUCCESS_COLOR
+        };
+        background.a *= fade;
+        draw_rectangle(x, y, width + margin * 2.0, height + margin, background);
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(
+                line,
+                x + margin,
+                y + margin / 2.0 + font_size * 0.8 + index as f32 * line_height,
+                font_size,
+                Color::new(1.0, 1.0, 1.0, fade),
+            );
+        }
+    }
+}
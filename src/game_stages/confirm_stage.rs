@@ -0,0 +1,67 @@
+use macroquad::prelude::*;
+use crate::assets::Assets;
+use crate::audio::AudioPlayer;
+use crate::input::{action::Action, action_input::ActionInput};
+use crate::tuning::Tuning;
+use super::{GameStage, StageTransition, PersistentData};
+use super::menu::Menu;
+
+/// Builds the transition to take once the player confirms, from the
+/// current tuning and screen size.
+pub type ConfirmAction = Box<dyn Fn(&Tuning, f32, f32) -> StageTransition>;
+
+/// A yes/no dialog pushed on top of another stage. "No" and Escape pop
+/// back to it.
+pub struct ConfirmStage {
+    question: String,
+    on_confirm: ConfirmAction,
+    menu: Menu,
+}
+
+impl ConfirmStage {
+    pub fn new(question: &str, on_confirm: ConfirmAction) -> Self {
+        Self {
+            question: question.to_string(),
+            on_confirm,
+            menu: Menu::new(),
+        }
+    }
+}
+
+impl GameStage for ConfirmStage {
+    fn update(
+        &mut self,
+        _dt: f32,
+        input: &ActionInput,
+        _persistent_data: &mut PersistentData,
+        _audio: &mut AudioPlayer,
+        tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> StageTransition {
+        if input.is_pressed(Action::Back) {
+            return StageTransition::Pop;
+        }
+        match self.menu.update(input, 2, screen_width, screen_height) {
+            Some(0) => (self.on_confirm)(tuning, screen_width, screen_height),
+            Some(_) => StageTransition::Pop,
+            None => StageTransition::None,
+        }
+    }
+
+    fn draw(
+        &self,
+        assets: &Assets,
+        _persistent_data: &PersistentData,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) {
+        let labels = vec!["Yes".to_string(), "No".to_string()];
+        self.menu.draw(&self.question, &labels, assets, screen_width, screen_height);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
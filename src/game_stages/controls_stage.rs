@@ -0,0 +1,118 @@
+use crate::assets::Assets;
+use crate::audio::AudioPlayer;
+use crate::input::{action::Action, action_input::ActionInput, bindings::{Binding, Bindings}};
+use crate::tuning::Tuning;
+use super::{GameStage, StageTransition, PersistentData};
+use super::menu::Menu;
+
+/// Actions listed for remapping. Menu directions are remapped in the save
+/// file, so a bad binding cannot lock the player out of this screen.
+const ACTION_ROWS: [Action; 6] = [
+    Action::Jump,
+    Action::FastFall,
+    Action::Pause,
+    Action::Confirm,
+    Action::Back,
+    Action::WatchReplay,
+];
+const RESET_ROW: usize = ACTION_ROWS.len();
+const BACK_ROW: usize = RESET_ROW + 1;
+
+/// Remaps the keys, mouse and gamepad buttons of each action. Activating a
+/// row waits for the next key or button, which becomes that action's only
+/// binding of its kind.
+/// Changes are saved when the stage exits.
+pub struct ControlsStage {
+    menu: Menu,
+    listening_for: Option<Action>,
+    /// The last binding refused for clashing, shown on its row: the action,
+    /// the binding and the action already using it.
+    rejected: Option<(Action, Binding, Action)>,
+}
+
+impl ControlsStage {
+    pub fn new() -> Self {
+        Self {
+            menu: Menu::new(),
+            listening_for: None,
+            rejected: None,
+        }
+    }
+}
+
+impl GameStage for ControlsStage {
+    fn update(
+        &mut self,
+        _dt: f32,
+        input: &ActionInput,
+        persistent_data: &mut PersistentData,
+        _audio: &mut AudioPlayer,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> StageTransition {
+        if let Some(action) = self.listening_for {
+            if let Some(binding) = input.new_binding {
+                self.rejected = persistent_data
+                    .settings
+                    .controls
+                    .rebind(action, binding)
+                    .err()
+                    .map(|clash| (action, binding, clash));
+                self.listening_for = None;
+            }
+            return StageTransition::None;
+        }
+
+        if input.is_pressed(Action::Back) {
+            return StageTransition::Pop;
+        }
+        match self.menu.update(input, BACK_ROW + 1, screen_width, screen_height) {
+            Some(BACK_ROW) => StageTransition::Pop,
+            Some(RESET_ROW) => {
+                persistent_data.settings.controls = Bindings::default();
+                StageTransition::None
+            }
+            Some(row) => {
+                self.listening_for = Some(ACTION_ROWS[row]);
+                self.rejected = None;
+                StageTransition::None
+            }
+            None => StageTransition::None,
+        }
+    }
+
+    fn draw(
+        &self,
+        assets: &Assets,
+        persistent_data: &PersistentData,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) {
+        let controls = &persistent_data.settings.controls;
+        let mut labels: Vec<String> = ACTION_ROWS
+            .iter()
+            .map(|&action| {
+                if self.listening_for == Some(action) {
+                    std::format!("{}: press a key or button", action.label())
+                } else if let Some((_, binding, clash)) = self.rejected.filter(|(rejected, _, _)| *rejected == action) {
+                    std::format!("{}: {} is {}'s", action.label(), binding.name(), clash.label())
+                } else {
+                    std::format!("{}: {}", action.label(), controls.label(action))
+                }
+            })
+            .collect();
+        labels.push("Reset Defaults".to_string());
+        labels.push("Back".to_string());
+        self.menu.draw("CONTROLS", &labels, assets, screen_width, screen_height);
+    }
+
+    fn on_exit(&mut self, persistent_data: &mut PersistentData) {
+        persistent_data.save();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,145 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use macroquad::prelude::*;
+use crate::config;
+use crate::simulation::world::World;
+use super::world_renderer;
+
+/// F3 overlay showing what the simulation is doing: hitboxes, drawn rects,
+/// the floor line, spawn and speed state, and frame and stage timings.
+pub struct DebugOverlay {
+    is_visible: bool,
+    frame_times: VecDeque<f32>,
+    update_time_s: f32,
+    // `draw` only gets `&self`, so its own timing is written back here.
+    draw_time_s: Cell<f32>,
+}
+
+impl DebugOverlay {
+    pub fn new() -> Self {
+        Self {
+            is_visible: false,
+            frame_times: VecDeque::with_capacity(config::debug::FRAME_GRAPH_SAMPLES),
+            update_time_s: 0.0,
+            draw_time_s: Cell::new(0.0),
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.is_visible
+    }
+
+    pub fn handle_input(&mut self) {
+        // A raw key rather than an action: this developer tool is not meant to be remapped or reached from a gamepad.
+        if is_key_pressed(config::debug::OVERLAY_TOGGLE_KEY) {
+            self.is_visible = !self.is_visible;
+        }
+    }
+
+    /// Adds a frame to the graph. The caller stops recording once the run
+    /// collided, which freezes the numbers on the collision frame.
+    pub fn record_frame(&mut self, dt: f32, update_time_s: f32) {
+        if self.frame_times.len() == config::debug::FRAME_GRAPH_SAMPLES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(dt);
+        self.update_time_s = update_time_s;
+    }
+
+    pub fn record_draw_time(&self, draw_time_s: f32) {
+        self.draw_time_s.set(draw_time_s);
+    }
+
+    pub fn draw(&self, world: &World, alpha: f32, screen_width: f32) {
+        if !self.is_visible {
+            return;
+        }
+
+        world_renderer::draw_hitboxes(world, alpha);
+        for obstacle in &world.obstacles {
+            let obstacle_x = obstacle.previous_x + (obstacle.rect.x - obstacle.previous_x) * alpha;
+            draw_rectangle_lines(
+                obstacle_x,
+                obstacle.rect.y,
+                obstacle.rect.w,
+                obstacle.rect.h,
+                1.0,
+                config::debug::RECT_COLOR,
+            );
+        }
+        draw_line(
+            0.0,
+            world.floor_y_position,
+            screen_width,
+            world.floor_y_position,
+            1.0,
+            config::debug::FLOOR_COLOR,
+        );
+
+        let lines = [
+            std::format!("FPS {}", get_fps()),
+            std::format!("update {:.2} ms   draw {:.2} ms", self.update_time_s * 1000.0, self.draw_time_s.get() * 1000.0),
+            std::format!("{}   scroll_speed {:.0}   tier {}", world.difficulty.label(), world.scroll_speed, world.speed_tier()),
+            std::format!("next_spawn {:.0} px   combo {}", world.next_spawn_distance, world.combo_remaining),
+            std::format!("unfair spawns held back {}", world.rejected_spawns),
+            std::format!("obstacles {}", world.obstacles.len()),
+            std::format!("distance {:.0}   score {}", world.distance, world.score),
+        ];
+        let font_size = config::debug::FONT_SIZE;
+        let line_height = font_size * 1.1;
+        let panel = Rect::new(
+            config::debug::PANEL_MARGIN,
+            config::debug::PANEL_Y,
+            config::debug::GRAPH_WIDTH + config::debug::PANEL_MARGIN * 2.0,
+            lines.len() as f32 * line_height + config::debug::GRAPH_HEIGHT + config::debug::PANEL_MARGIN * 3.0,
+        );
+        draw_rectangle(panel.x, panel.y, panel.w, panel.h, config::debug::PANEL_COLOR);
+        let text_x = panel.x + config::debug::PANEL_MARGIN;
+        let mut text_y = panel.y + config::debug::PANEL_MARGIN + font_size * 0.8;
+        for line in &lines {
+            draw_text(line, text_x, text_y, font_size, WHITE);
+            text_y += line_height;
+        }
+        if world.is_over {
+            draw_text(
+                "COLLISION - F3 to continue",
+                text_x,
+                panel.bottom() + font_size,
+                font_size,
+                config::debug::HITBOX_COLOR,
+            );
+        }
+
+        self.draw_frame_graph(Rect::new(
+            text_x,
+            panel.bottom() - config::debug::PANEL_MARGIN - config::debug::GRAPH_HEIGHT,
+            config::debug::GRAPH_WIDTH,
+            config::debug::GRAPH_HEIGHT,
+        ));
+    }
+
+    /// One bar per recent frame, scaled so the top of the graph is twice
+    /// the 60 Hz budget, with a line marking the budget.
+    fn draw_frame_graph(&self, area: Rect) {
+        draw_rectangle(area.x, area.y, area.w, area.h, config::debug::GRAPH_BACKGROUND);
+        let full_scale = config::FIXED_TIMESTEP * 2.0;
+        let bar_width = area.w / config::debug::FRAME_GRAPH_SAMPLES as f32;
+        for (index, &frame_time) in self.frame_times.iter().enumerate() {
+            let height = (frame_time / full_scale).min(1.0) * area.h;
+            let color = if frame_time > config::FIXED_TIMESTEP * 1.05 {
+                config::debug::SLOW_FRAME_COLOR
+            } else {
+                config::debug::FRAME_COLOR
+            };
+            draw_rectangle(
+                area.x + index as f32 * bar_width,
+                area.bottom() - height,
+                bar_width,
+                height,
+                color,
+            );
+        }
+        let budget_y = area.bottom() - area.h / 2.0;
+        draw_line(area.x, budget_y, area.right(), budget_y, 1.0, WHITE);
+    }
+}
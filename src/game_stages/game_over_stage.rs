@@ -1,20 +1,81 @@
 use macroquad::prelude::*;
 use crate::assets::Assets;
 use crate::config;
+use crate::audio::{AudioPlayer, MusicTrack};
+use crate::simulation::replay::Replay;
+use crate::input::{action::Action, action_input::ActionInput};
+use crate::tuning::Tuning;
+use crate::types::{difficulty::Difficulty, run_mode::RunMode};
 use super::{GameStage, StageTransition, PersistentData};
 use super::playing_stage::PlayingStage;
+use super::replay_stage::ReplayStage;
 
 pub struct GameOverStage {
+    run_mode: RunMode,
+    difficulty: Difficulty,
     final_score: u32,
+    stars: u32,
+    replay: Option<Replay>,
+    /// The button under the mouse, highlighted.
+    hovered: Option<usize>,
+}
+
+/// The buttons along the bottom of the screen, so touch and mouse players
+/// can pick either without a key.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GameOverButton {
+    Restart,
+    WatchReplay,
 }
 
 impl GameOverStage {
     pub fn new(
+        run_mode: RunMode,
+        difficulty: Difficulty,
         score: u32,
+        stars: u32,
+        replay: Option<Replay>,
         _screen_width: f32,
         _screen_height: f32,
     ) -> Self {
-        Self { final_score: score }
+        Self {
+            run_mode,
+            difficulty,
+            final_score: score,
+            stars,
+            replay,
+            hovered: None,
+        }
+    }
+
+    /// A hot reload since the run can leave its replay unplayable, which
+    /// hides its button.
+    fn playable_replay(&self, tuning: &Tuning) -> Option<&Replay> {
+        self.replay.as_ref().filter(|replay| replay.check_tuning(tuning).is_ok())
+    }
+
+    fn buttons(&self, tuning: &Tuning) -> Vec<GameOverButton> {
+        let mut buttons = vec![GameOverButton::Restart];
+        if self.playable_replay(tuning).is_some() {
+            buttons.push(GameOverButton::WatchReplay);
+        }
+        buttons
+    }
+
+    fn button_rect(index: usize, count: usize, screen_width: f32, screen_height: f32) -> Rect {
+        let width = config::menu_ui::ITEM_WIDTH / 1.5;
+        let step = width + config::menu_ui::ITEM_SPACING;
+        let total_width = count as f32 * step - config::menu_ui::ITEM_SPACING;
+        Rect::new(
+            screen_width / 2.0 - total_width / 2.0 + index as f32 * step,
+            screen_height * 0.8,
+            width,
+            config::menu_ui::ITEM_HEIGHT,
+        )
+    }
+
+    fn button_at(point: Vec2, count: usize, screen_width: f32, screen_height: f32) -> Option<usize> {
+        (0..count).find(|&index| Self::button_rect(index, count, screen_width, screen_height).contains(point))
     }
 }
 
@@ -22,27 +83,64 @@ impl GameStage for GameOverStage {
     fn update(
         &mut self,
         _dt: f32,
+        input: &ActionInput,
         _persistent_data: &mut PersistentData,
+        _audio: &mut AudioPlayer,
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     ) -> StageTransition {
-        if is_key_pressed(KeyCode::Space) {
-            return StageTransition::Switch(
+        let buttons = self.buttons(tuning);
+        self.hovered = Self::button_at(input.pointer, buttons.len(), screen_width, screen_height);
+        let tapped = input
+            .tap
+            .and_then(|tap| Self::button_at(tap, buttons.len(), screen_width, screen_height))
+            .map(|index| buttons[index]);
+        // A click or tap only acts through the buttons, even when Jump is
+        // bound to it, so touch players can reach the replay.
+        let jumped = input.is_pressed(Action::Jump) && input.tap.is_none();
+        let pressed = if jumped || input.is_pressed(Action::Confirm) {
+            Some(GameOverButton::Restart)
+        } else if input.is_pressed(Action::WatchReplay) {
+            Some(GameOverButton::WatchReplay)
+        } else {
+            tapped
+        };
+
+        match pressed {
+            Some(GameOverButton::Restart) => StageTransition::Switch(
                 Box::new(
                     PlayingStage::new(
+                        self.run_mode,
+                        self.difficulty,
+                        tuning,
                         screen_width,
                         screen_height,
                     )
                 )
-            );
+            ),
+            Some(GameOverButton::WatchReplay) => match self.playable_replay(tuning) {
+                Some(replay) => StageTransition::Replace(
+                    Box::new(
+                        ReplayStage::new(
+                            replay.clone(),
+                            tuning,
+                            screen_width,
+                            screen_height,
+                        )
+                    )
+                ),
+                None => StageTransition::None,
+            },
+            None => StageTransition::None,
         }
-        StageTransition::None
     }
 
     fn draw(
         &self,
-        _assets: &Assets,
+        assets: &Assets,
         persistent_data: &PersistentData,
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     ) {
@@ -51,14 +149,14 @@ impl GameStage for GameOverStage {
         let game_over_text = "GAME OVER!";
         let text_dims_game_over = measure_text(
             game_over_text, None,
-            config::game_over_ui::FONT_SIZE as u16,
+            tuning.game_over_ui.font_size as u16,
             1.0,
         );
         draw_text(
             game_over_text,
             screen_width / 2.0 - text_dims_game_over.width / 2.0,
             screen_height / 2.0,
-            config::game_over_ui::FONT_SIZE,
+            tuning.game_over_ui.font_size,
             BLACK,
         );
 
@@ -66,45 +164,107 @@ impl GameStage for GameOverStage {
         let final_score_dims = measure_text(
             &final_score_text,
             None,
-            config::game_over_ui::SCORE_FONT_SIZE as u16,
+            tuning.game_over_ui.score_font_size as u16,
             1.0,
         );
         draw_text(
             &final_score_text,
             screen_width / 2.0 - final_score_dims.width / 2.0,
-            screen_height / 2.0 + config::game_over_ui::FONT_SIZE + 10.0,
-            config::game_over_ui::SCORE_FONT_SIZE,
+            screen_height / 2.0 + tuning.game_over_ui.font_size + 10.0,
+            tuning.game_over_ui.score_font_size,
             DARKGRAY,
         );
 
-        let high_score_text = std::format!("High Score: {}", persistent_data.high_score);
+        let high_score_text = match self.run_mode {
+            RunMode::Endless => std::format!("High Score: {}", persistent_data.high_score),
+            RunMode::Daily { day } => std::format!(
+                "Today's Daily Best: {}",
+                persistent_data.daily_high_score(day),
+            ),
+        };
         let high_score_dims = measure_text(
             &high_score_text,
             None,
-            config::game_over_ui::SCORE_FONT_SIZE as u16,
+            tuning.game_over_ui.score_font_size as u16,
             1.0,
         );
         draw_text(
             &high_score_text,
             screen_width / 2.0 - high_score_dims.width / 2.0,
-            screen_height / 2.0 + config::game_over_ui::FONT_SIZE + 10.0 + config::game_over_ui::SCORE_FONT_SIZE + 10.0,
-            config::game_over_ui::SCORE_FONT_SIZE,
+            screen_height / 2.0 + tuning.game_over_ui.font_size + 10.0 + tuning.game_over_ui.score_font_size + 10.0,
+            tuning.game_over_ui.score_font_size,
+            DARKGRAY,
+        );
+
+        let stars_text = std::format!(
+            "Stars: {}   Lifetime: {}",
+            self.stars,
+            persistent_data.total_stars,
+        );
+        let stars_dims = measure_text(
+            &stars_text,
+            None,
+            tuning.game_over_ui.score_font_size as u16,
+            1.0,
+        );
+        draw_text(
+            &stars_text,
+            screen_width / 2.0 - stars_dims.width / 2.0,
+            screen_height / 2.0 + tuning.game_over_ui.font_size + 10.0 + (tuning.game_over_ui.score_font_size + 10.0) * 2.0,
+            tuning.game_over_ui.score_font_size,
             DARKGRAY,
         );
 
-        let restart_prompt_text = "Press SPACE to Restart";
+        let controls = &persistent_data.settings.controls;
+        let buttons = self.buttons(tuning);
+        for (index, &button) in buttons.iter().enumerate() {
+            let rect = Self::button_rect(index, buttons.len(), screen_width, screen_height);
+            let color = if self.hovered == Some(index) {
+                config::menu_ui::SELECTED_ITEM_COLOR
+            } else {
+                config::menu_ui::ITEM_COLOR
+            };
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+            let label = match button {
+                GameOverButton::Restart => std::format!("Restart ({})", controls.label(Action::Confirm)),
+                GameOverButton::WatchReplay => std::format!("Replay ({})", controls.label(Action::WatchReplay)),
+            };
+            let label_dims = measure_text(
+                &label,
+                Some(&assets.bold_font),
+                config::menu_ui::FONT_SIZE as u16,
+                1.0,
+            );
+            draw_text_ex(
+                &label,
+                rect.x + (rect.w - label_dims.width) / 2.0,
+                rect.y + (rect.h - label_dims.height) / 2.0 + label_dims.offset_y,
+                TextParams {
+                    font: Some(&assets.bold_font),
+                    font_size: config::menu_ui::FONT_SIZE as u16,
+                    color: WHITE,
+                    ..TextParams::default()
+                },
+            );
+        }
+
+        let restart_prompt_text = std::format!("or press {} to Restart", controls.label(Action::Jump));
         let restart_prompt_dims = measure_text(
-            restart_prompt_text,
+            &restart_prompt_text,
             None,
-            config::game_over_ui::SCORE_FONT_SIZE as u16,
+            tuning.game_over_ui.score_font_size as u16,
             1.0,
         );
         draw_text(
-            restart_prompt_text,
+            &restart_prompt_text,
             screen_width / 2.0 - restart_prompt_dims.width / 2.0,
-            screen_height * 0.8,
-            config::game_over_ui::SCORE_FONT_SIZE,
-            BLACK,
+            screen_height * 0.8 + config::menu_ui::ITEM_HEIGHT + tuning.game_over_ui.score_font_size,
+            tuning.game_over_ui.score_font_size,
+            DARKGRAY,
         );
     }
-}
\ No newline at end of file
+
+    fn music(&self) -> Option<MusicTrack> {
+        Some(MusicTrack::GameOver)
+    }
+}
@@ -1,19 +1,32 @@
 use macroquad::prelude::*;
 use crate::assets::Assets;
-use crate::config;
+use crate::audio::{AudioPlayer, MusicTrack};
+use crate::input::{action::Action, action_input::ActionInput};
+use crate::tuning::Tuning;
+use crate::types::run_mode::RunMode;
 use super::{GameStage, StageTransition, PersistentData};
+use super::menu::Menu;
 use super::playing_stage::PlayingStage;
 
+const START_ROW: usize = 0;
+const DAILY_ROW: usize = 1;
+const DIFFICULTY_ROW: usize = 2;
+
 pub struct IntroStage {
     slides: Vec<String>,
     current_slide_index: usize,
     slide_timer: f32,
     start_button_rect: Rect,
+    daily_button_rect: Rect,
+    difficulty_button_rect: Rect,
+    /// Picks between the buttons once the last slide shows.
+    menu: Menu,
     first_frame_passed: bool,
 }
 
 impl IntroStage {
     pub fn new(
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     ) -> Self {
@@ -33,9 +46,22 @@ impl IntroStage {
             start_button_rect: Rect::new(
                 screen_width * 0.4,
                 screen_height * 0.7,
-                config::intro::BUTTON_WIDTH,
-                config::intro::BUTTON_HEIGHT,
+                tuning.intro.button_width,
+                tuning.intro.button_height,
+            ),
+            daily_button_rect: Rect::new(
+                screen_width * 0.4,
+                screen_height * 0.7 + tuning.intro.button_height + tuning.intro.button_spacing,
+                tuning.intro.button_width,
+                tuning.intro.button_height,
             ),
+            difficulty_button_rect: Rect::new(
+                screen_width * 0.4,
+                screen_height * 0.7 + (tuning.intro.button_height + tuning.intro.button_spacing) * 2.0,
+                tuning.intro.button_width,
+                tuning.intro.button_height,
+            ),
+            menu: Menu::new(),
             first_frame_passed: false,
         }
     }
@@ -45,14 +71,33 @@ impl GameStage for IntroStage {
     fn update(
         &mut self,
         dt: f32,
-        _persistent_data: &mut PersistentData,
+        input: &ActionInput,
+        persistent_data: &mut PersistentData,
+        _audio: &mut AudioPlayer,
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     ) -> StageTransition {
         self.start_button_rect.x = screen_width * 0.37;
         self.start_button_rect.y = screen_height * 0.5;
-        self.start_button_rect.w = config::intro::BUTTON_WIDTH;
-        self.start_button_rect.h = config::intro::BUTTON_HEIGHT;
+        self.start_button_rect.w = tuning.intro.button_width;
+        self.start_button_rect.h = tuning.intro.button_height;
+        self.daily_button_rect = Rect::new(
+            self.start_button_rect.x,
+            self.start_button_rect.y + tuning.intro.button_height + tuning.intro.button_spacing,
+            tuning.intro.button_width,
+            tuning.intro.button_height,
+        );
+        self.difficulty_button_rect = Rect::new(
+            self.daily_button_rect.x,
+            self.daily_button_rect.y + tuning.intro.button_height + tuning.intro.button_spacing,
+            tuning.intro.button_width,
+            tuning.intro.button_height,
+        );
+
+        if input.is_pressed(Action::Back) {
+            return StageTransition::Quit;
+        }
 
         if self.current_slide_index == 0 && !self.first_frame_passed {
             self.first_frame_passed = true;
@@ -62,7 +107,7 @@ impl GameStage for IntroStage {
             }
         } else {
             self.slide_timer += dt;
-            if self.slide_timer >= config::intro::SLIDE_DURATION_S {
+            if self.slide_timer >= tuning.intro.slide_duration_s {
                 self.slide_timer = 0.0;
                 if self.current_slide_index < self.slides.len() - 1 {
                     self.current_slide_index += 1;
@@ -70,13 +115,37 @@ impl GameStage for IntroStage {
             }
         }
 
-        if is_mouse_button_pressed(MouseButton::Left) {
-            let (mouse_x, mouse_y) = mouse_position();
-            if self.start_button_rect.contains(Vec2::new(mouse_x, mouse_y))
-                && self.current_slide_index == self.slides.len() - 1 {
+        if self.current_slide_index == self.slides.len() - 1 {
+            let button_rects = [self.start_button_rect, self.daily_button_rect, self.difficulty_button_rect];
+            let activated = self.menu.update_buttons(input, &button_rects);
+            if self.menu.selected() == DIFFICULTY_ROW
+                && (input.is_pressed(Action::Left) || input.is_pressed(Action::Right)) {
+                let settings = &mut persistent_data.settings;
+                settings.difficulty = if input.is_pressed(Action::Left) {
+                    settings.difficulty.previous()
+                } else {
+                    settings.difficulty.next()
+                };
+                persistent_data.save();
+            }
+            let run_mode = match activated {
+                Some(DIFFICULTY_ROW) => {
+                    let settings = &mut persistent_data.settings;
+                    settings.difficulty = settings.difficulty.next();
+                    persistent_data.save();
+                    None
+                }
+                Some(DAILY_ROW) => Some(RunMode::daily_today()),
+                Some(_) => Some(RunMode::Endless),
+                None => None,
+            };
+            if let Some(run_mode) = run_mode {
                 return StageTransition::Switch(
                     Box::new(
                         PlayingStage::new(
+                            run_mode,
+                            persistent_data.settings.difficulty,
+                            tuning,
                             screen_width,
                             screen_height,
                         )
@@ -90,7 +159,8 @@ impl GameStage for IntroStage {
     fn draw(
         &self,
         _assets: &Assets,
-        _persistent_data: &PersistentData,
+        persistent_data: &PersistentData,
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     ) {
@@ -102,8 +172,8 @@ impl GameStage for IntroStage {
         let current_slide_full_text = &self.slides[self.current_slide_index];
         let lines: Vec<&str> = current_slide_full_text.lines().collect();
 
-        let font_size = config::intro::FONT_SIZE;
-        let line_spacing = font_size * config::intro::LINE_HEIGHT_FACTOR;
+        let font_size = tuning.intro.font_size;
+        let line_spacing = font_size * tuning.intro.line_height_factor;
         let total_text_block_height = if lines.is_empty() {
             0.0
         } else {
@@ -141,33 +211,44 @@ impl GameStage for IntroStage {
         }
 
         if self.current_slide_index == self.slides.len() - 1 {
-            draw_rectangle(
-                self.start_button_rect.x,
-                self.start_button_rect.y,
-                self.start_button_rect.w,
-                self.start_button_rect.h,
-                BLACK,
-            );
-            let button_text = "Start Game";
-            let button_font_size = config::intro::FONT_SIZE;
-            let button_text_dims = measure_text(
-                button_text,
-                None,
-                button_font_size as u16,
-                1.0,
-            );
-            let text_params = TextParams {
-                font: Some(&_assets.bold_font),
-                color:WHITE,
-                font_size: button_font_size as u16,
-                ..TextParams::default()
-            };
-            draw_text_ex(
-                button_text,
-                self.start_button_rect.x + (self.start_button_rect.w - button_text_dims.width) / 2.0,
-                self.start_button_rect.y + (self.start_button_rect.h - button_text_dims.height) / 2.0 + button_text_dims.offset_y,
-                text_params,
-            );
+            let selected = self.menu.selected();
+            draw_button(&self.start_button_rect, "Start Game", selected == START_ROW, tuning, _assets);
+            draw_button(&self.daily_button_rect, "Daily Run", selected == DAILY_ROW, tuning, _assets);
+            let difficulty_label = std::format!("Difficulty: < {} >", persistent_data.settings.difficulty.label());
+            draw_button(&self.difficulty_button_rect, &difficulty_label, selected == DIFFICULTY_ROW, tuning, _assets);
         }
     }
+
+    fn music(&self) -> Option<MusicTrack> {
+        Some(MusicTrack::Intro)
+    }
+}
+
+fn draw_button(rect: &Rect, label: &str, is_selected: bool, tuning: &Tuning, assets: &Assets) {
+    draw_rectangle(
+        rect.x,
+        rect.y,
+        rect.w,
+        rect.h,
+        if is_selected { BLACK } else { DARKGRAY },
+    );
+    let button_font_size = tuning.intro.font_size;
+    let button_text_dims = measure_text(
+        label,
+        None,
+        button_font_size as u16,
+        1.0,
+    );
+    let text_params = TextParams {
+        font: Some(&assets.bold_font),
+        color:WHITE,
+        font_size: button_font_size as u16,
+        ..TextParams::default()
+    };
+    draw_text_ex(
+        label,
+        rect.x + (rect.w - button_text_dims.width) / 2.0,
+        rect.y + (rect.h - button_text_dims.height) / 2.0 + button_text_dims.offset_y,
+        text_params,
+    );
 }
\ No newline at end of file
@@ -0,0 +1,123 @@
+use macroquad::prelude::*;
+use crate::assets::Assets;
+use crate::config;
+use crate::input::{action::Action, action_input::ActionInput};
+
+/// A vertical list of buttons shared by the overlay stages. Items are
+/// picked with the Up/Down and Confirm actions, or by clicking or tapping
+/// them.
+pub struct Menu {
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new() -> Self {
+        Self { selected: 0 }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn item_rect(index: usize, item_count: usize, screen_width: f32, screen_height: f32) -> Rect {
+        let step = config::menu_ui::ITEM_HEIGHT + config::menu_ui::ITEM_SPACING;
+        let total_height = item_count as f32 * step - config::menu_ui::ITEM_SPACING;
+        Rect::new(
+            screen_width / 2.0 - config::menu_ui::ITEM_WIDTH / 2.0,
+            screen_height * 0.55 - total_height / 2.0 + index as f32 * step,
+            config::menu_ui::ITEM_WIDTH,
+            config::menu_ui::ITEM_HEIGHT,
+        )
+    }
+
+    /// Moves the selection and returns the index of an item that was
+    /// activated this frame.
+    pub fn update(
+        &mut self,
+        input: &ActionInput,
+        item_count: usize,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Option<usize> {
+        let item_rects: Vec<Rect> = (0..item_count)
+            .map(|index| Self::item_rect(index, item_count, screen_width, screen_height))
+            .collect();
+        self.update_buttons(input, &item_rects)
+    }
+
+    /// Like `update`, for a stage that lays out and draws its own buttons.
+    pub fn update_buttons(&mut self, input: &ActionInput, item_rects: &[Rect]) -> Option<usize> {
+        let item_count = item_rects.len();
+        if input.is_pressed(Action::Down) {
+            self.selected = (self.selected + 1) % item_count;
+        }
+        if input.is_pressed(Action::Up) {
+            self.selected = (self.selected + item_count - 1) % item_count;
+        }
+
+        let item_at = |point: Vec2| item_rects.iter().position(|rect| rect.contains(point));
+        if input.pointer_moved
+            && let Some(hovered) = item_at(input.pointer) {
+            self.selected = hovered;
+        }
+        if let Some(tapped) = input.tap.and_then(item_at) {
+            self.selected = tapped;
+            return Some(tapped);
+        }
+
+        if input.is_pressed(Action::Confirm) {
+            return Some(self.selected);
+        }
+        None
+    }
+
+    pub fn draw(&self, title: &str, labels: &[String], assets: &Assets, screen_width: f32, screen_height: f32) {
+        draw_rectangle(0.0, 0.0, screen_width, screen_height, config::menu_ui::OVERLAY_COLOR);
+
+        let first_item = Self::item_rect(0, labels.len(), screen_width, screen_height);
+        let title_dims = measure_text(
+            title,
+            Some(&assets.bold_font),
+            config::menu_ui::TITLE_FONT_SIZE as u16,
+            1.0,
+        );
+        draw_text_ex(
+            title,
+            screen_width / 2.0 - title_dims.width / 2.0,
+            first_item.y - config::menu_ui::TITLE_FONT_SIZE,
+            TextParams {
+                font: Some(&assets.bold_font),
+                font_size: config::menu_ui::TITLE_FONT_SIZE as u16,
+                color: WHITE,
+                ..TextParams::default()
+            },
+        );
+
+        for (index, label) in labels.iter().enumerate() {
+            let rect = Self::item_rect(index, labels.len(), screen_width, screen_height);
+            let color = if index == self.selected {
+                config::menu_ui::SELECTED_ITEM_COLOR
+            } else {
+                config::menu_ui::ITEM_COLOR
+            };
+            draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+            let label_dims = measure_text(
+                label,
+                Some(&assets.bold_font),
+                config::menu_ui::FONT_SIZE as u16,
+                1.0,
+            );
+            draw_text_ex(
+                label,
+                rect.x + (rect.w - label_dims.width) / 2.0,
+                rect.y + (rect.h - label_dims.height) / 2.0 + label_dims.offset_y,
+                TextParams {
+                    font: Some(&assets.bold_font),
+                    font_size: config::menu_ui::FONT_SIZE as u16,
+                    color: WHITE,
+                    ..TextParams::default()
+                },
+            );
+        }
+    }
+}
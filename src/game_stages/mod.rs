@@ -1,28 +1,101 @@
+use std::time::{SystemTime, UNIX_EPOCH};
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
 use crate::assets::Assets;
+use crate::audio::{AudioPlayer, MusicTrack};
+use crate::input::action_input::ActionInput;
+use crate::state::{save_file, settings::Settings};
+use crate::tuning::Tuning;
+use crate::types::{run_mode::RunMode, score_type::SPEED_TIER_COUNT};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PersistentData {
     pub high_score: u32,
+    pub total_runs: u32,
+    /// Distance scrolled over all runs, in world pixels.
+    pub total_distance: f64,
+    /// Stars collected over all runs.
+    pub total_stars: u64,
+    /// Best score of runs that ended in each speed tier, indexed by `ScoreType::speed_tier`.
+    pub best_per_speed_tier: [u32; SPEED_TIER_COUNT],
+    /// Unix timestamp in seconds of the last finished run.
+    pub last_played: Option<u64>,
+    pub daily_best: Option<DailyBest>,
+    pub settings: Settings,
+    /// Set when the save file was written by a newer build, so this
+    /// session plays on fresh data and never overwrites it.
+    #[serde(skip)]
+    pub is_read_only: bool,
+}
+
+/// Best score on one day's Daily Run course.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DailyBest {
+    pub day: u64,
+    pub score: u32,
 }
 
 impl PersistentData {
-    pub fn new() -> Self {
-        Default::default()
+    pub fn load() -> Self {
+        save_file::load()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = save_file::save(self) {
+            warn!("Failed to write save file: {}", e);
+        }
+    }
+
+    pub fn daily_high_score(&self, day: u64) -> u32 {
+        match self.daily_best {
+            Some(best) if best.day == day => best.score,
+            _ => 0,
+        }
+    }
+
+    pub fn record_run(&mut self, run_mode: RunMode, score: u32, stars: u32, distance: f32, speed_tier: usize) {
+        if let RunMode::Daily { day } = run_mode
+            && score >= self.daily_high_score(day) {
+            self.daily_best = Some(DailyBest { day, score });
+        }
+        self.high_score = self.high_score.max(score);
+        self.total_runs += 1;
+        self.total_distance += distance as f64;
+        self.total_stars += stars as u64;
+        let tier_best = &mut self.best_per_speed_tier[speed_tier.min(SPEED_TIER_COUNT - 1)];
+        *tier_best = (*tier_best).max(score);
+        self.last_played = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|elapsed| elapsed.as_secs());
     }
 }
 
 pub enum StageTransition {
     None,
+    /// Replaces every stage on the stack with the given one.
     Switch(Box<dyn GameStage>),
+    /// Replaces only the top stage, keeping the ones below it.
+    Replace(Box<dyn GameStage>),
+    /// Puts a stage on top; the stages below stay drawn but stop updating.
+    Push(Box<dyn GameStage>),
+    /// Removes the top stage and resumes the one below it.
+    Pop,
+    /// Leaves the game.
+    Quit,
 }
 
 pub trait GameStage {
 
+    #[allow(clippy::too_many_arguments)]
     fn update(
         &mut self,
         dt: f32,
+        input: &ActionInput,
         persistent_data: &mut PersistentData,
+        audio: &mut AudioPlayer,
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     ) -> StageTransition;
@@ -31,11 +104,49 @@ pub trait GameStage {
         &self,
         assets: &Assets,
         persistent_data: &PersistentData,
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     );
+
+    /// Called when the stage is put on the stack.
+    fn on_enter(&mut self, _persistent_data: &mut PersistentData) {}
+
+    /// Called when the stage is removed from the stack.
+    fn on_exit(&mut self, _persistent_data: &mut PersistentData) {}
+
+    /// Called when another stage is pushed on top of this one.
+    fn on_pause(&mut self, _persistent_data: &mut PersistentData) {}
+
+    /// Called when this stage is back on top after the one above it popped.
+    fn on_resume(&mut self, _persistent_data: &mut PersistentData) {}
+
+    /// Called on every stage in the stack when the tuning file was reloaded
+    /// in dev mode. Values read from `tuning` each frame need nothing here.
+    fn on_tuning_reloaded(&mut self, _tuning: &Tuning) {}
+
+    /// Overlays are drawn on top of the full-screen stage below them
+    /// instead of replacing it.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+
+    /// Background music to loop while this stage is on screen. Overlays
+    /// return `None` to keep the music of the stage below them.
+    fn music(&self) -> Option<MusicTrack> {
+        None
+    }
 }
 
 pub mod intro_stage;
 pub mod playing_stage;
-pub mod game_over_stage;
\ No newline at end of file
+pub mod confirm_stage;
+pub mod controls_stage;
+pub mod debug_overlay;
+pub mod game_over_stage;
+pub mod menu;
+pub mod pause_stage;
+pub mod replay_stage;
+pub mod settings_stage;
+pub mod stage_manager;
+pub mod world_renderer;
\ No newline at end of file
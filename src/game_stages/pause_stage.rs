@@ -0,0 +1,108 @@
+use macroquad::prelude::*;
+use crate::assets::Assets;
+use crate::audio::AudioPlayer;
+use crate::input::{action::Action, action_input::ActionInput};
+use crate::tuning::Tuning;
+use crate::types::{difficulty::Difficulty, run_mode::RunMode};
+use super::{GameStage, StageTransition, PersistentData};
+use super::confirm_stage::ConfirmStage;
+use super::intro_stage::IntroStage;
+use super::menu::Menu;
+use super::playing_stage::PlayingStage;
+use super::settings_stage::SettingsStage;
+
+const ITEMS: [&str; 4] = ["Resume", "Restart", "Settings", "Quit to Intro"];
+
+/// Overlay pushed on top of a frozen `PlayingStage`.
+pub struct PauseStage {
+    run_mode: RunMode,
+    difficulty: Difficulty,
+    menu: Menu,
+}
+
+impl PauseStage {
+    pub fn new(run_mode: RunMode, difficulty: Difficulty) -> Self {
+        Self {
+            run_mode,
+            difficulty,
+            menu: Menu::new(),
+        }
+    }
+}
+
+impl GameStage for PauseStage {
+    fn update(
+        &mut self,
+        _dt: f32,
+        input: &ActionInput,
+        _persistent_data: &mut PersistentData,
+        _audio: &mut AudioPlayer,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> StageTransition {
+        if input.is_pressed(Action::Back) || input.is_pressed(Action::Pause) {
+            return StageTransition::Pop;
+        }
+
+        match self.menu.update(input, ITEMS.len(), screen_width, screen_height) {
+            Some(0) => StageTransition::Pop,
+            Some(1) => {
+                let run_mode = self.run_mode;
+                let difficulty = self.difficulty;
+                StageTransition::Push(
+                    Box::new(
+                        ConfirmStage::new(
+                            "RESTART RUN?",
+                            Box::new(move |tuning, screen_width, screen_height| StageTransition::Switch(
+                                Box::new(
+                                    PlayingStage::new(
+                                        run_mode,
+                                        difficulty,
+                                        tuning,
+                                        screen_width,
+                                        screen_height,
+                                    )
+                                )
+                            )),
+                        )
+                    )
+                )
+            }
+            Some(2) => StageTransition::Push(Box::new(SettingsStage::new())),
+            Some(_) => StageTransition::Push(
+                Box::new(
+                    ConfirmStage::new(
+                        "QUIT THIS RUN?",
+                        Box::new(|tuning, screen_width, screen_height| StageTransition::Switch(
+                            Box::new(
+                                IntroStage::new(
+                                    tuning,
+                                    screen_width,
+                                    screen_height,
+                                )
+                            )
+                        )),
+                    )
+                )
+            ),
+            None => StageTransition::None,
+        }
+    }
+
+    fn draw(
+        &self,
+        assets: &Assets,
+        _persistent_data: &PersistentData,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) {
+        let labels: Vec<String> = ITEMS.iter().map(|item| item.to_string()).collect();
+        self.menu.draw("PAUSED", &labels, assets, screen_width, screen_height);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
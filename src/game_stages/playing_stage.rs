@@ -1,36 +1,168 @@
+use std::cmp::Ordering;
 use macroquad::prelude::*;
+use crate::animation::{
+    particles::RunParticles, pickup_effects::PickupEffects, player_animator::PlayerAnimator,
+};
 use crate::assets::Assets;
+use crate::audio::{AudioPlayer, MusicTrack, SoundCue};
 use crate::config;
-use crate::types::{obstacle::Obstacle, obstacle_type::ObstacleType, score_type::ScoreType};
+use crate::input::{action::Action, action_input::ActionInput};
+use crate::simulation::{
+    ghost::Ghost, replay::ReplayRecorder, sim_event::SimEvent, sim_input::SimInput, world::World,
+};
+use crate::state::replay_file;
+use crate::tuning::Tuning;
+use crate::types::{difficulty::Difficulty, run_mode::RunMode};
 use super::{GameStage, StageTransition, PersistentData};
+use super::debug_overlay::DebugOverlay;
 use super::game_over_stage::GameOverStage;
+use super::pause_stage::PauseStage;
+use super::world_renderer;
 
 pub struct PlayingStage {
-    player_y_position: f32,
-    player_velocity_y: f32,
-    is_on_floor: bool,
-    obstacles: Vec<Obstacle>,
-    spawn_timer: f32,
-    scroll_speed: f32,
-    score: u32,
-    score_timer: f32,
-    floor_y_position: f32,
+    run_mode: RunMode,
+    world: World,
+    recorder: Option<ReplayRecorder>,
+    ghost: Option<Ghost>,
+    player_animator: PlayerAnimator,
+    ghost_animator: PlayerAnimator,
+    pickup_effects: PickupEffects,
+    particles: RunParticles,
+    game_over: Option<GameOverStage>,
+    jump_requested: bool,
+    accumulator: f32,
+    debug_overlay: DebugOverlay,
 }
 
 impl PlayingStage {
-    pub fn new(_screen_width: f32, _screen_height: f32) -> Self {
-        let floor_y_position = _screen_height * 0.65;
+    /// `difficulty` is the player's chosen preset; Daily Runs ignore it.
+    pub fn new(
+        run_mode: RunMode,
+        difficulty: Difficulty,
+        tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> Self {
+        let difficulty = run_mode.difficulty(difficulty);
+        let ghost = replay_file::load_ghost(run_mode, difficulty, tuning).map(|replay| Ghost::new(replay, tuning));
+        // Racing a ghost only makes sense on the exact course it was recorded on.
+        let (spawn_x_position, floor_y_position) = match &ghost {
+            Some(ghost) => (ghost.replay().spawn_x_position, ghost.replay().floor_y_position),
+            None => (screen_width, screen_height * 0.65),
+        };
+        let seed = run_mode.seed();
+        let world = World::new(spawn_x_position, floor_y_position, seed, difficulty, tuning);
         Self {
-            player_y_position: 100.0,
-            player_velocity_y: 0.0,
-            is_on_floor: false,
-            obstacles: Vec::new(),
-            spawn_timer: 2.0,
-            scroll_speed: config::SCROLL_SPEED_INITIAL,
-            score: 0,
-            score_timer: 0.0,
-            floor_y_position,
+            run_mode,
+            particles: RunParticles::new(&world),
+            world,
+            recorder: Some(ReplayRecorder::new(
+                run_mode,
+                difficulty,
+                seed,
+                spawn_x_position,
+                floor_y_position,
+                tuning,
+            )),
+            ghost,
+            player_animator: PlayerAnimator::new(),
+            ghost_animator: PlayerAnimator::new(),
+            pickup_effects: PickupEffects::new(),
+            game_over: None,
+            jump_requested: false,
+            accumulator: 0.0,
+            debug_overlay: DebugOverlay::new(),
+        }
+    }
+
+    fn draw_ghost(&self, ghost: &Ghost, tuning: &Tuning, assets: &Assets, screen_width: f32) {
+        if !ghost.is_finished() {
+            let ghost_world = ghost.world();
+            let alpha = self.interpolation_alpha();
+            let ghost_y_position = ghost_world.previous_player_y_position
+                + (ghost_world.player_y_position - ghost_world.previous_player_y_position) * alpha;
+            world_renderer::draw_player(
+                assets,
+                &ghost_world.tuning().player,
+                config::ghost::X_OFFSET,
+                ghost_y_position,
+                self.ghost_animator.current_frame(),
+                config::ghost::TINT,
+            );
+        }
+
+        let lead = ghost.lead_over(self.world.score);
+        let (lead_text, lead_color) = match lead.cmp(&0) {
+            Ordering::Greater => (std::format!("AHEAD OF BEST +{}", lead), config::ghost::AHEAD_COLOR),
+            Ordering::Equal => ("EVEN WITH BEST".to_string(), config::ghost::EVEN_COLOR),
+            Ordering::Less => (std::format!("BEHIND BEST {}", lead), config::ghost::BEHIND_COLOR),
+        };
+        let lead_dims = measure_text(
+            &lead_text,
+            Some(&assets.bold_font),
+            config::ghost::LEAD_FONT_SIZE as u16,
+            1.0,
+        );
+        draw_text_ex(
+            &lead_text,
+            screen_width - lead_dims.width - tuning.playing_ui.score_margin,
+            tuning.playing_ui.score_margin + tuning.playing_ui.score_font_size * 2.0,
+            TextParams {
+                font: Some(&assets.bold_font),
+                font_size: config::ghost::LEAD_FONT_SIZE as u16,
+                color: lead_color,
+                ..TextParams::default()
+            },
+        );
+    }
+
+    /// Saves the finished run's stats and replays, and builds the stage
+    /// shown once the death animation is over.
+    fn finish_run(
+        &mut self,
+        persistent_data: &mut PersistentData,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> GameOverStage {
+        let is_new_daily_best = match self.run_mode {
+            RunMode::Daily { day } => self.world.score >= persistent_data.daily_high_score(day),
+            RunMode::Endless => false,
+        };
+        let replay = self.recorder
+            .take()
+            .map(|recorder| recorder.finish(self.world.score, self.world.stars_collected));
+        if let Some(replay) = &replay {
+            replay_file::save(replay_file::LAST_RUN, replay);
+            if is_new_daily_best {
+                replay_file::save(replay_file::DAILY_BEST_RUN, replay);
+            }
         }
+        persistent_data.record_run(
+            self.run_mode,
+            self.world.score,
+            self.world.stars_collected,
+            self.world.distance,
+            self.world.speed_tier(),
+        );
+        persistent_data.save();
+        GameOverStage::new(
+            self.run_mode,
+            self.world.difficulty,
+            self.world.score,
+            self.world.stars_collected,
+            replay,
+            screen_width,
+            screen_height,
+        )
+    }
+
+    /// How far the renderer is between the last two simulation steps, in `[0, 1)`.
+    fn interpolation_alpha(&self) -> f32 {
+        // A finished world no longer steps, so show its final frame.
+        if self.world.is_over {
+            return 1.0;
+        }
+        self.accumulator / config::FIXED_TIMESTEP
     }
 }
 
@@ -38,113 +170,89 @@ impl GameStage for PlayingStage {
     fn update(
         &mut self,
         dt: f32,
+        input: &ActionInput,
         persistent_data: &mut PersistentData,
+        audio: &mut AudioPlayer,
+        _tuning: &Tuning,
         screen_width: f32,
-        _screen_height: f32,
+        screen_height: f32,
     ) -> StageTransition {
-        if is_key_pressed(KeyCode::Space) && self.is_on_floor {
-            self.player_velocity_y = config::JUMP_FORCE;
+        let wants_pause = input.is_pressed(Action::Back)
+            || input.is_pressed(Action::Pause)
+            || dt > config::pause::FOCUS_LOSS_FRAME_TIME;
+        if wants_pause && !self.world.is_over {
+            return StageTransition::Push(
+                Box::new(PauseStage::new(self.run_mode, self.world.difficulty))
+            );
         }
-        self.player_velocity_y += config::GRAVITY;
-        self.player_y_position += self.player_velocity_y;
 
-        let player_height = config::PLAYER_RADIUS * 2.0;
-        if self.player_y_position < player_height {
-            self.player_y_position = player_height;
-            self.player_velocity_y = 0.0;
+        self.debug_overlay.handle_input();
+        // Hold the collision frame, death animation and all, while the
+        // debug overlay is open.
+        if self.world.is_over && self.debug_overlay.is_visible() {
+            return StageTransition::None;
         }
-
-        if self.player_y_position >= self.floor_y_position {
-            self.player_y_position = self.floor_y_position;
-            self.player_velocity_y = 0.0;
-            self.is_on_floor = true;
-        } else {
-            self.is_on_floor = false;
+        let update_start = get_time();
+        if input.is_pressed(Action::Jump) {
+            self.jump_requested = true;
         }
 
-        self.score_timer += dt;
-        if self.score_timer >= 0.1 {
-            self.score += 1;
-            self.score_timer = 0.0;
+        self.accumulator += dt.min(config::MAX_FRAME_TIME);
+        while self.accumulator >= config::FIXED_TIMESTEP {
+            self.accumulator -= config::FIXED_TIMESTEP;
+            let input = SimInput {
+                jump_pressed: std::mem::take(&mut self.jump_requested),
+                jump_held: input.is_down(Action::Jump),
+                fast_fall: input.is_down(Action::FastFall),
+            };
+            if let Some(recorder) = self.recorder.as_mut() {
+                recorder.record(input);
+            }
+            let events = self.world.step(input, config::FIXED_TIMESTEP);
+            for &event in &events {
+                if let Some(cue) = SoundCue::for_event(event) {
+                    audio.play(cue);
+                }
+                if event == SimEvent::StarCollected {
+                    self.pickup_effects.spawn(self.world.player_hitbox().center);
+                }
+                self.particles.on_event(event, &self.world);
+            }
+            if let Some(ghost) = self.ghost.as_mut() {
+                ghost.step();
+            }
+            if events.contains(&SimEvent::Collided) {
+                self.game_over = Some(self.finish_run(persistent_data, screen_width, screen_height));
+                break;
+            }
         }
 
-        self.scroll_speed = match self.score {
-            s if s >= ScoreType::FifthValue.value() => config::SCROLL_SPEED_TIER5,
-            s if s >= ScoreType::FourthValue.value() => config::SCROLL_SPEED_TIER4,
-            s if s >= ScoreType::ThirdValue.value() => config::SCROLL_SPEED_TIER3,
-            s if s >= ScoreType::SecondValue.value() => config::SCROLL_SPEED_TIER2,
-            s if s >= ScoreType::FirstValue.value() => config::SCROLL_SPEED_TIER1,
-            _ => config::SCROLL_SPEED_INITIAL,
-        };
-
-        self.spawn_timer -= dt;
-        if self.spawn_timer <= 0.0 {
-            self.spawn_timer = rand::gen_range(
-                config::OBSTACLE_SPAWN_TIMER_MIN,
-                config::OBSTACLE_SPAWN_TIMER_MAX,
+        self.player_animator.update(
+            dt,
+            self.world.is_on_floor,
+            self.world.player_velocity_y,
+            self.world.is_over,
+        );
+        self.pickup_effects.update(dt);
+        self.particles.update(dt, &self.world);
+        if let Some(ghost) = &self.ghost {
+            let ghost_world = ghost.world();
+            self.ghost_animator.update(
+                dt,
+                ghost_world.is_on_floor,
+                ghost_world.player_velocity_y,
+                ghost_world.is_over,
             );
-            let random_kind_index = rand::gen_range(0, 3);
-            let new_obstacle = match random_kind_index {
-                0 => Obstacle {
-                    rect: Rect::new(
-                        screen_width,
-                        self.floor_y_position - config::OBSTACLE_DEFAULT_HEIGHT,
-                        config::OBSTACLE_DEFAULT_WIDTH,
-                        config::OBSTACLE_DEFAULT_HEIGHT,
-                    ),
-                    kind: ObstacleType::Object0,
-                },
-                1 => {
-                    let object1_rect_y = self.floor_y_position - config::OBSTACLE_DEFAULT_HEIGHT
-                        - config::OBJECT1_ADDITIONAL_CLEARANCE_ABOVE_OTHERS - config::OBJECT1_OWN_HEIGHT;
-                    Obstacle {
-                        rect: Rect::new(
-                            screen_width,
-                            object1_rect_y,
-                            config::OBJECT1_OWN_WIDTH,
-                            config::OBJECT1_OWN_HEIGHT,
-                        ),
-                        kind: ObstacleType::Object1,
-                    }
-                },
-                _ => Obstacle {
-                    rect: Rect::new(
-                        screen_width,
-                        self.floor_y_position - config::OBSTACLE_DEFAULT_HEIGHT,
-                        config::OBSTACLE_DEFAULT_WIDTH,
-                        config::OBSTACLE_DEFAULT_HEIGHT,
-                    ),
-                    kind: ObstacleType::Object2,
-                },
-            };
-            self.obstacles.push(new_obstacle);
         }
 
-        for obstacle in self.obstacles.iter_mut() {
-            obstacle.rect.x -= self.scroll_speed * dt;
+        if !self.world.is_over {
+            self.debug_overlay.record_frame(dt, (get_time() - update_start) as f32);
         }
-        self.obstacles.retain(|o| o.rect.x + o.rect.w > 0.0);
-
-        let player_collision_rect = Rect::new(
-            config::PLAYER_X_POSITION - config::PLAYER_RADIUS,
-            self.player_y_position - player_height,
-            config::PLAYER_RADIUS * 2.0,
-            player_height,
-        );
 
-        for obstacle in &self.obstacles {
-            if player_collision_rect.overlaps(&obstacle.rect) {
-                persistent_data.high_score = persistent_data.high_score.max(self.score);
-                return StageTransition::Switch(
-                    Box::new(
-                        GameOverStage::new(
-                            self.score,
-                            screen_width,
-                            _screen_height,
-                        )
-                    )
-                );
-            }
+        // Let the death clip play out before leaving the frozen world.
+        if self.player_animator.is_finished()
+            && let Some(game_over) = self.game_over.take() {
+            return StageTransition::Switch(Box::new(game_over));
         }
 
         StageTransition::None
@@ -154,123 +262,48 @@ impl GameStage for PlayingStage {
         &self,
         assets: &Assets,
         _persistent_data: &PersistentData,
+        tuning: &Tuning,
         screen_width: f32,
         screen_height: f32,
     ) {
-        clear_background(WHITE);
-
-        let bg_scale = 0.15;
-        let bg_scaled_width = assets.background.width() * bg_scale;
-        let bg_scaled_height = assets.background.height() * bg_scale;
-        let bg_x_pos = (screen_width / 2.0) - (bg_scaled_width / 2.0);
-        let bg_y_pos = screen_height - bg_scaled_height;
-        draw_texture_ex(
-            &assets.background,
-            bg_x_pos,
-            bg_y_pos,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(
-                    Vec2::new(
-                        bg_scaled_width,
-                        bg_scaled_height,
-                    )
-                ),
-                ..Default::default()
-            }
-        );
-
-        draw_line(
-            0.0,
-            self.floor_y_position ,
+        let draw_start = get_time();
+        world_renderer::draw_world(
+            &self.world,
+            self.interpolation_alpha(),
+            self.player_animator.current_frame(),
+            assets,
             screen_width,
-            self.floor_y_position ,
-            3.0,
-            BLACK,
-        );
-
-        let desired_player_collision_height = config::PLAYER_RADIUS * 2.0;
-        let player_texture_height = assets.player.height();
-        let player_display_scale = desired_player_collision_height / player_texture_height;
-        let player_display_width = assets.player.width() * player_display_scale;
-
-        let player_draw_x = config::PLAYER_X_POSITION - player_display_width / 2.0;
-        let player_draw_y = self.player_y_position - desired_player_collision_height;
-
-        draw_texture_ex(
-            &assets.player,
-            player_draw_x,
-            player_draw_y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(
-                    Vec2::new(
-                        player_display_width,
-                        desired_player_collision_height,
-                    )
-                ),
-                ..Default::default()
-            },
+            screen_height,
         );
+        self.particles.draw(&self.world, &assets.particle);
+        self.pickup_effects.draw(assets, tuning.stars.size);
+        let subtitle = match self.run_mode {
+            RunMode::Endless => None,
+            RunMode::Daily { .. } => Some("DAILY RUN"),
+        };
+        world_renderer::draw_hud(&self.world, subtitle, tuning, assets, screen_width);
 
-        for obstacle in &self.obstacles {
-            let texture = match obstacle.kind {
-                ObstacleType::Object0 => &assets.object0,
-                ObstacleType::Object1 => &assets.object1,
-                ObstacleType::Object2 => &assets.object2,
-            };
-            draw_texture_ex(
-                texture,
-                obstacle.rect.x,
-                obstacle.rect.y,
-                WHITE,
-                DrawTextureParams {
-                    dest_size: Some(
-                        Vec2::new(
-                            obstacle.rect.w,
-                            obstacle.rect.h,
-                        )
-                    ),
-                    ..Default::default()
-                },
-            );
+        if let Some(ghost) = &self.ghost {
+            self.draw_ghost(ghost, tuning, assets, screen_width);
         }
 
-        let text_params = TextParams {
-            font: Some(&assets.bold_font),
-            font_size: 30.0 as u16,
-            color: BLACK,
-            ..TextParams::default()
-        };
+        self.debug_overlay.draw(&self.world, self.interpolation_alpha(), screen_width);
+        self.debug_overlay.record_draw_time((get_time() - draw_start) as f32);
+    }
 
-        draw_text_ex(
-            "SOOT SPRINT",
-            20.0,
-            50.0,
-            text_params,
-        );
+    fn on_resume(&mut self, _persistent_data: &mut PersistentData) {
+        // Presses made in the pause menu must not leak into the run.
+        self.jump_requested = false;
+        self.accumulator = 0.0;
+    }
 
-        let score_text = std::format!("SCORE: {}", self.score);
-        let text_dimensions = measure_text(
-            &score_text,
-            None,
-            config::playing_ui::SCORE_FONT_SIZE as u16,
-            1.0,
-        );
-        let text_x = screen_width - text_dimensions.width - config::playing_ui::SCORE_MARGIN;
-        let text_y = config::playing_ui::SCORE_MARGIN + config::playing_ui::SCORE_FONT_SIZE;
+    fn on_tuning_reloaded(&mut self, tuning: &Tuning) {
+        self.world.set_tuning(tuning);
+        // The recorded input would not reproduce a run with mixed tuning.
+        self.recorder = None;
+    }
 
-        let score_text_params = TextParams {
-            font: Some(&assets.bold_font),
-            font_size: 24.0 as u16,
-            color: BLACK,
-            ..TextParams::default()
-        };
-        draw_text_ex(
-            &score_text,
-            text_x,
-            text_y,
-            score_text_params,
-        );
+    fn music(&self) -> Option<MusicTrack> {
+        Some(MusicTrack::Playing)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,220 @@
+use macroquad::prelude::*;
+use crate::animation::{
+    particles::RunParticles, pickup_effects::PickupEffects, player_animator::PlayerAnimator,
+};
+use crate::assets::Assets;
+use crate::audio::{AudioPlayer, MusicTrack, SoundCue};
+use crate::config;
+use crate::input::{action::Action, action_input::ActionInput};
+use crate::simulation::{replay::Replay, sim_event::SimEvent, world::World};
+use crate::tuning::Tuning;
+use super::{GameStage, StageTransition, PersistentData};
+use super::debug_overlay::DebugOverlay;
+use super::game_over_stage::GameOverStage;
+use super::world_renderer;
+
+/// Plays a recorded run back through the same `World` simulation as
+/// `PlayingStage`, with pause, speed control and frame stepping.
+pub struct ReplayStage {
+    replay: Replay,
+    world: World,
+    player_animator: PlayerAnimator,
+    pickup_effects: PickupEffects,
+    particles: RunParticles,
+    tick: u32,
+    accumulator: f32,
+    speed_index: usize,
+    is_paused: bool,
+    debug_overlay: DebugOverlay,
+}
+
+impl ReplayStage {
+    pub fn new(replay: Replay, tuning: &Tuning, _screen_width: f32, _screen_height: f32) -> Self {
+        let world = World::new(
+            replay.spawn_x_position,
+            replay.floor_y_position,
+            replay.seed,
+            replay.difficulty,
+            tuning,
+        );
+        Self {
+            replay,
+            particles: RunParticles::new(&world),
+            world,
+            player_animator: PlayerAnimator::new(),
+            pickup_effects: PickupEffects::new(),
+            tick: 0,
+            accumulator: 0.0,
+            speed_index: config::replay_ui::DEFAULT_SPEED_INDEX,
+            is_paused: false,
+            debug_overlay: DebugOverlay::new(),
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.world.is_over || self.tick >= self.replay.total_ticks
+    }
+
+    fn step(&mut self, audio: &AudioPlayer) {
+        if self.is_finished() {
+            return;
+        }
+        let input = self.replay.input_at(self.tick);
+        for event in self.world.step(input, config::FIXED_TIMESTEP) {
+            if let Some(cue) = SoundCue::for_event(event) {
+                audio.play(cue);
+            }
+            if event == SimEvent::StarCollected {
+                self.pickup_effects.spawn(self.world.player_hitbox().center);
+            }
+            self.particles.on_event(event, &self.world);
+        }
+        self.tick += 1;
+    }
+
+    fn interpolation_alpha(&self) -> f32 {
+        self.accumulator / config::FIXED_TIMESTEP
+    }
+}
+
+impl GameStage for ReplayStage {
+    fn update(
+        &mut self,
+        dt: f32,
+        input: &ActionInput,
+        _persistent_data: &mut PersistentData,
+        audio: &mut AudioPlayer,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> StageTransition {
+        if input.is_pressed(Action::Back) || input.is_pressed(Action::Confirm) {
+            return StageTransition::Replace(
+                Box::new(
+                    GameOverStage::new(
+                        self.replay.run_mode,
+                        self.replay.difficulty,
+                        self.replay.final_score,
+                        self.replay.final_stars,
+                        Some(self.replay.clone()),
+                        screen_width,
+                        screen_height,
+                    )
+                )
+            );
+        }
+
+        self.debug_overlay.handle_input();
+        let update_start = get_time();
+        if input.is_pressed(Action::Pause) {
+            self.is_paused = !self.is_paused;
+        }
+        let speed_count = config::replay_ui::SPEEDS.len();
+        if input.is_pressed(Action::Up) {
+            self.speed_index = (self.speed_index + 1).min(speed_count - 1);
+        }
+        if input.is_pressed(Action::Down) {
+            self.speed_index = self.speed_index.saturating_sub(1);
+        }
+
+        let animation_dt = if self.is_paused {
+            if input.is_pressed(Action::Right) {
+                self.accumulator = 0.0;
+                self.step(audio);
+                config::FIXED_TIMESTEP
+            } else {
+                0.0
+            }
+        } else {
+            let scaled_dt = dt.min(config::MAX_FRAME_TIME) * config::replay_ui::SPEEDS[self.speed_index];
+            self.accumulator += scaled_dt;
+            while self.accumulator >= config::FIXED_TIMESTEP {
+                self.accumulator -= config::FIXED_TIMESTEP;
+                self.step(audio);
+            }
+            if self.is_finished() {
+                self.accumulator = 0.0;
+            }
+            scaled_dt
+        };
+        self.pickup_effects.update(animation_dt);
+        self.particles.update(animation_dt, &self.world);
+        self.player_animator.update(
+            animation_dt,
+            self.world.is_on_floor,
+            self.world.player_velocity_y,
+            self.world.is_over,
+        );
+        if !self.is_finished() {
+            self.debug_overlay.record_frame(dt, (get_time() - update_start) as f32);
+        }
+
+        StageTransition::None
+    }
+
+    fn draw(
+        &self,
+        assets: &Assets,
+        persistent_data: &PersistentData,
+        tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) {
+        let draw_start = get_time();
+        world_renderer::draw_world(
+            &self.world,
+            self.interpolation_alpha(),
+            self.player_animator.current_frame(),
+            assets,
+            screen_width,
+            screen_height,
+        );
+        self.particles.draw(&self.world, &assets.particle);
+        self.pickup_effects.draw(assets, tuning.stars.size);
+
+        let speed = config::replay_ui::SPEEDS[self.speed_index];
+        let status = if self.is_finished() {
+            "REPLAY FINISHED".to_string()
+        } else if self.is_paused {
+            std::format!("REPLAY {}x PAUSED", speed)
+        } else {
+            std::format!("REPLAY {}x", speed)
+        };
+        world_renderer::draw_hud(&self.world, Some(&status), tuning, assets, screen_width);
+
+        let controls = &persistent_data.settings.controls;
+        let back_text = std::format!("{}: back", controls.label(Action::Back));
+        let controls_text = if self.is_finished() {
+            back_text
+        } else {
+            std::format!(
+                "{}: pause   {}/{}: speed   {}: step frame   {}",
+                controls.label(Action::Pause),
+                controls.label(Action::Up),
+                controls.label(Action::Down),
+                controls.label(Action::Right),
+                back_text,
+            )
+        };
+        let controls_dims = measure_text(
+            &controls_text,
+            None,
+            config::replay_ui::CONTROLS_FONT_SIZE as u16,
+            1.0,
+        );
+        draw_text(
+            &controls_text,
+            screen_width / 2.0 - controls_dims.width / 2.0,
+            screen_height - config::replay_ui::CONTROLS_FONT_SIZE,
+            config::replay_ui::CONTROLS_FONT_SIZE,
+            DARKGRAY,
+        );
+
+        self.debug_overlay.draw(&self.world, self.interpolation_alpha(), screen_width);
+        self.debug_overlay.record_draw_time((get_time() - draw_start) as f32);
+    }
+
+    fn music(&self) -> Option<MusicTrack> {
+        Some(MusicTrack::Playing)
+    }
+}
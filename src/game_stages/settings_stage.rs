@@ -0,0 +1,112 @@
+use macroquad::prelude::*;
+use crate::assets::Assets;
+use crate::audio::AudioPlayer;
+use crate::config;
+use crate::input::{action::Action, action_input::ActionInput};
+use crate::tuning::Tuning;
+use super::{GameStage, StageTransition, PersistentData};
+use super::controls_stage::ControlsStage;
+use super::menu::Menu;
+
+const VOLUME_ROWS: usize = 3;
+const CONTROLS_ROW: usize = VOLUME_ROWS;
+const BACK_ROW: usize = CONTROLS_ROW + 1;
+
+/// Volume settings. Left/Right change the selected volume, clicking a row
+/// steps it up and wraps around. Changes are saved when the stage exits.
+/// The Controls row opens the remapping screen.
+pub struct SettingsStage {
+    menu: Menu,
+}
+
+impl SettingsStage {
+    pub fn new() -> Self {
+        Self { menu: Menu::new() }
+    }
+
+    fn volume_mut(persistent_data: &mut PersistentData, row: usize) -> &mut f32 {
+        let audio = &mut persistent_data.settings.audio;
+        match row {
+            0 => &mut audio.master_volume,
+            1 => &mut audio.music_volume,
+            _ => &mut audio.sfx_volume,
+        }
+    }
+
+    fn adjust(persistent_data: &mut PersistentData, row: usize, delta: f32) {
+        let volume = Self::volume_mut(persistent_data, row);
+        // Round to whole steps so repeated presses do not drift.
+        let steps = ((*volume + delta) / config::settings_ui::VOLUME_STEP).round();
+        *volume = (steps * config::settings_ui::VOLUME_STEP).clamp(0.0, 1.0);
+    }
+}
+
+impl GameStage for SettingsStage {
+    fn update(
+        &mut self,
+        _dt: f32,
+        input: &ActionInput,
+        persistent_data: &mut PersistentData,
+        _audio: &mut AudioPlayer,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> StageTransition {
+        if input.is_pressed(Action::Back) {
+            return StageTransition::Pop;
+        }
+
+        let activated = self.menu.update(input, BACK_ROW + 1, screen_width, screen_height);
+        let selected = self.menu.selected();
+        if selected < VOLUME_ROWS {
+            if input.is_pressed(Action::Left) {
+                Self::adjust(persistent_data, selected, -config::settings_ui::VOLUME_STEP);
+            }
+            if input.is_pressed(Action::Right) {
+                Self::adjust(persistent_data, selected, config::settings_ui::VOLUME_STEP);
+            }
+        }
+
+        match activated {
+            Some(BACK_ROW) => StageTransition::Pop,
+            Some(CONTROLS_ROW) => StageTransition::Push(Box::new(ControlsStage::new())),
+            Some(row) => {
+                let volume = Self::volume_mut(persistent_data, row);
+                if *volume >= 1.0 {
+                    *volume = 0.0;
+                } else {
+                    Self::adjust(persistent_data, row, config::settings_ui::VOLUME_STEP);
+                }
+                StageTransition::None
+            }
+            None => StageTransition::None,
+        }
+    }
+
+    fn draw(
+        &self,
+        assets: &Assets,
+        persistent_data: &PersistentData,
+        _tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) {
+        let audio = &persistent_data.settings.audio;
+        let labels = vec![
+            std::format!("Master  < {:.0}% >", audio.master_volume * 100.0),
+            std::format!("Music  < {:.0}% >", audio.music_volume * 100.0),
+            std::format!("Effects  < {:.0}% >", audio.sfx_volume * 100.0),
+            "Controls".to_string(),
+            "Back".to_string(),
+        ];
+        self.menu.draw("SETTINGS", &labels, assets, screen_width, screen_height);
+    }
+
+    fn on_exit(&mut self, persistent_data: &mut PersistentData) {
+        persistent_data.save();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,265 @@
+use crate::assets::Assets;
+use crate::audio::{AudioPlayer, MusicTrack};
+use crate::input::action_input::ActionInput;
+use crate::tuning::Tuning;
+use super::{GameStage, StageTransition, PersistentData};
+
+/// Owns the stack of stages, applies the transitions they return and calls
+/// their lifecycle hooks. Only the top stage is updated.
+pub struct StageManager {
+    stages: Vec<Box<dyn GameStage>>,
+}
+
+impl StageManager {
+    pub fn new(mut first_stage: Box<dyn GameStage>, persistent_data: &mut PersistentData) -> Self {
+        first_stage.on_enter(persistent_data);
+        Self {
+            stages: vec![first_stage],
+        }
+    }
+
+    /// Updates the top stage and applies its transition. Returns `false`
+    /// once a stage asked to quit the game.
+    #[allow(clippy::too_many_arguments)]
+    pub fn update(
+        &mut self,
+        dt: f32,
+        input: &ActionInput,
+        persistent_data: &mut PersistentData,
+        audio: &mut AudioPlayer,
+        tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) -> bool {
+        let Some(top_stage) = self.stages.last_mut() else {
+            return false;
+        };
+        let transition = top_stage.update(
+            dt,
+            input,
+            persistent_data,
+            audio,
+            tuning,
+            screen_width,
+            screen_height,
+        );
+        self.apply(transition, persistent_data)
+    }
+
+    /// Applies a transition returned by the top stage, calling the hooks of
+    /// the stages it moves. Returns `false` for `Quit`.
+    fn apply(&mut self, transition: StageTransition, persistent_data: &mut PersistentData) -> bool {
+        match transition {
+            StageTransition::None => {}
+            StageTransition::Switch(new_stage) => {
+                self.exit_all(persistent_data);
+                self.enter(new_stage, persistent_data);
+            }
+            StageTransition::Replace(new_stage) => {
+                if let Some(mut old_stage) = self.stages.pop() {
+                    old_stage.on_exit(persistent_data);
+                }
+                self.enter(new_stage, persistent_data);
+            }
+            StageTransition::Push(new_stage) => {
+                if let Some(paused_stage) = self.stages.last_mut() {
+                    paused_stage.on_pause(persistent_data);
+                }
+                self.enter(new_stage, persistent_data);
+            }
+            StageTransition::Pop => {
+                // The bottom stage has nothing to return to.
+                if self.stages.len() > 1 {
+                    if let Some(mut old_stage) = self.stages.pop() {
+                        old_stage.on_exit(persistent_data);
+                    }
+                    if let Some(resumed_stage) = self.stages.last_mut() {
+                        resumed_stage.on_resume(persistent_data);
+                    }
+                }
+            }
+            StageTransition::Quit => {
+                self.exit_all(persistent_data);
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Draws the topmost full-screen stage and, if the top of the stack is
+    /// an overlay, that overlay on top of it. Overlays in between are
+    /// skipped so menus opened from menus do not pile up.
+    pub fn draw(
+        &self,
+        assets: &Assets,
+        persistent_data: &PersistentData,
+        tuning: &Tuning,
+        screen_width: f32,
+        screen_height: f32,
+    ) {
+        for index in self.drawn_indices() {
+            self.stages[index].draw(assets, persistent_data, tuning, screen_width, screen_height);
+        }
+    }
+
+    /// Indices of the stages `draw` draws, bottom first.
+    fn drawn_indices(&self) -> Vec<usize> {
+        let base_index = self.stages
+            .iter()
+            .rposition(|stage| !stage.is_overlay())
+            .unwrap_or(0);
+        let top_index = self.stages.len() - 1;
+        if top_index == base_index {
+            vec![base_index]
+        } else {
+            vec![base_index, top_index]
+        }
+    }
+
+    pub fn reload_tuning(&mut self, tuning: &Tuning) {
+        for stage in self.stages.iter_mut() {
+            stage.on_tuning_reloaded(tuning);
+        }
+    }
+
+    /// Music of the topmost stage that asks for any.
+    pub fn music(&self) -> Option<MusicTrack> {
+        self.stages.iter().rev().find_map(|stage| stage.music())
+    }
+
+    fn enter(&mut self, mut stage: Box<dyn GameStage>, persistent_data: &mut PersistentData) {
+        stage.on_enter(persistent_data);
+        self.stages.push(stage);
+    }
+
+    fn exit_all(&mut self, persistent_data: &mut PersistentData) {
+        while let Some(mut stage) = self.stages.pop() {
+            stage.on_exit(persistent_data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use super::*;
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    /// A stage that only records its hooks.
+    struct Stub {
+        name: &'static str,
+        is_overlay: bool,
+        log: Log,
+    }
+
+    impl Stub {
+        fn boxed(name: &'static str, is_overlay: bool, log: &Log) -> Box<dyn GameStage> {
+            Box::new(Self { name, is_overlay, log: log.clone() })
+        }
+
+        fn record(&self, hook: &str) {
+            self.log.borrow_mut().push(std::format!("{} {}", self.name, hook));
+        }
+    }
+
+    impl GameStage for Stub {
+        fn update(
+            &mut self,
+            _dt: f32,
+            _input: &ActionInput,
+            _persistent_data: &mut PersistentData,
+            _audio: &mut AudioPlayer,
+            _tuning: &Tuning,
+            _screen_width: f32,
+            _screen_height: f32,
+        ) -> StageTransition {
+            StageTransition::None
+        }
+
+        fn draw(&self, _: &Assets, _: &PersistentData, _: &Tuning, _: f32, _: f32) {}
+
+        fn on_enter(&mut self, _persistent_data: &mut PersistentData) {
+            self.record("enter");
+        }
+
+        fn on_exit(&mut self, _persistent_data: &mut PersistentData) {
+            self.record("exit");
+        }
+
+        fn on_pause(&mut self, _persistent_data: &mut PersistentData) {
+            self.record("pause");
+        }
+
+        fn on_resume(&mut self, _persistent_data: &mut PersistentData) {
+            self.record("resume");
+        }
+
+        fn is_overlay(&self) -> bool {
+            self.is_overlay
+        }
+    }
+
+    fn take(log: &Log) -> Vec<String> {
+        log.borrow_mut().drain(..).collect()
+    }
+
+    #[test]
+    fn transitions_call_the_hooks_in_order() {
+        let log = Log::default();
+        let mut data = PersistentData::default();
+        let mut manager = StageManager::new(Stub::boxed("run", false, &log), &mut data);
+        assert_eq!(take(&log), ["run enter"]);
+
+        assert!(manager.apply(StageTransition::Push(Stub::boxed("pause", true, &log)), &mut data));
+        assert_eq!(take(&log), ["run pause", "pause enter"]);
+        assert_eq!(manager.stages.len(), 2);
+
+        assert!(manager.apply(StageTransition::Replace(Stub::boxed("confirm", true, &log)), &mut data));
+        assert_eq!(take(&log), ["pause exit", "confirm enter"]);
+        assert_eq!(manager.stages.len(), 2);
+
+        assert!(manager.apply(StageTransition::Pop, &mut data));
+        assert_eq!(take(&log), ["confirm exit", "run resume"]);
+        assert_eq!(manager.stages.len(), 1);
+
+        // The last stage stays, since there is nothing to return to.
+        assert!(manager.apply(StageTransition::Pop, &mut data));
+        assert!(take(&log).is_empty());
+        assert_eq!(manager.stages.len(), 1);
+
+        manager.apply(StageTransition::Push(Stub::boxed("pause", true, &log)), &mut data);
+        take(&log);
+        assert!(manager.apply(StageTransition::Switch(Stub::boxed("intro", false, &log)), &mut data));
+        assert_eq!(take(&log), ["pause exit", "run exit", "intro enter"]);
+        assert_eq!(manager.stages.len(), 1);
+
+        assert!(manager.apply(StageTransition::None, &mut data));
+        assert!(take(&log).is_empty());
+
+        manager.apply(StageTransition::Push(Stub::boxed("pause", true, &log)), &mut data);
+        take(&log);
+        assert!(!manager.apply(StageTransition::Quit, &mut data));
+        assert_eq!(take(&log), ["pause exit", "intro exit"]);
+        assert!(manager.stages.is_empty());
+    }
+
+    #[test]
+    fn the_top_full_screen_stage_is_drawn_under_the_top_overlay() {
+        let log = Log::default();
+        let mut data = PersistentData::default();
+        let mut manager = StageManager::new(Stub::boxed("run", false, &log), &mut data);
+        assert_eq!(manager.drawn_indices(), [0]);
+
+        manager.apply(StageTransition::Push(Stub::boxed("pause", true, &log)), &mut data);
+        assert_eq!(manager.drawn_indices(), [0, 1]);
+
+        // An overlay under the top one is skipped.
+        manager.apply(StageTransition::Push(Stub::boxed("settings", true, &log)), &mut data);
+        assert_eq!(manager.drawn_indices(), [0, 2]);
+
+        manager.apply(StageTransition::Push(Stub::boxed("game over", false, &log)), &mut data);
+        assert_eq!(manager.drawn_indices(), [3]);
+    }
+}
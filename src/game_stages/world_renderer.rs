@@ -0,0 +1,358 @@
+use macroquad::prelude::*;
+use crate::animation::animation_clip::AnimationFrame;
+use crate::assets::Assets;
+use crate::config;
+use crate::simulation::{collision::Shape, power_ups, world::World};
+use crate::tuning::{PlayerTuning, Tuning};
+use crate::types::background::LayerAnchor;
+
+/// Draws a `World` the same way for every stage that shows a run.
+/// `alpha` interpolates between the last two fixed simulation steps.
+pub fn draw_world(
+    world: &World,
+    alpha: f32,
+    player_frame: &AnimationFrame,
+    assets: &Assets,
+    screen_width: f32,
+    screen_height: f32,
+) {
+    clear_background(WHITE);
+    draw_background(world, alpha, assets, screen_width, screen_height);
+
+    let player_y_position = world.previous_player_y_position
+        + (world.player_y_position - world.previous_player_y_position) * alpha;
+    draw_player(assets, &world.tuning().player, 0.0, player_y_position, player_frame, WHITE);
+    // An effect that takes a hit for the player shows as a bubble.
+    if let Some(shield) = world.power_ups.iter().find(|power_up| power_up.effect.absorbs_collision()) {
+        let player = &world.tuning().player;
+        let mut bubble_color = shield.effect.color();
+        bubble_color.a = config::power_ups::SHIELD_BUBBLE_ALPHA;
+        draw_circle(
+            player.x_position,
+            player_y_position - player.hitbox_center_height,
+            player.radius * 1.2,
+            bubble_color,
+        );
+    }
+
+    let time = get_time() as f32;
+    let kinds = &world.tuning().obstacles.kinds;
+    for obstacle in &world.obstacles {
+        // Both can be missing for a moment while a hot reload changes the
+        // catalogue.
+        let (Some(kind), Some(texture)) = (
+            kinds.get(obstacle.kind),
+            assets.obstacle_textures.get(obstacle.kind),
+        ) else {
+            continue;
+        };
+        let obstacle_x = obstacle.previous_x + (obstacle.rect.x - obstacle.previous_x) * alpha;
+        let frame = assets.obstacle_animations
+            .for_placement(kind.placement)
+            .frame_at(time);
+        draw_sprite(
+            texture,
+            Vec2::new(obstacle_x + obstacle.rect.w / 2.0, obstacle.rect.bottom()),
+            Vec2::new(obstacle.rect.w, obstacle.rect.h),
+            frame,
+            WHITE,
+        );
+    }
+
+    let star_size = world.tuning().stars.size;
+    for star in &world.stars {
+        let star_x = star.previous_x + (star.center.x - star.previous_x) * alpha;
+        // A gentle bob, offset along the line so it ripples.
+        let bob = (time * config::stars::BOB_SPEED + star.center.x * 0.05).sin() * config::stars::BOB_HEIGHT;
+        draw_star(assets, Vec2::new(star_x, star.center.y + bob), star_size, WHITE);
+    }
+
+    let pickup_radius = world.tuning().power_ups.size / 2.0;
+    for pickup in &world.power_up_pickups {
+        let pickup_x = pickup.previous_x + (pickup.center.x - pickup.previous_x) * alpha;
+        let bob = (time * config::stars::BOB_SPEED).sin() * config::stars::BOB_HEIGHT;
+        let center = Vec2::new(pickup_x, pickup.center.y + bob);
+        let effect = power_ups::create(pickup.kind, &world.tuning().power_ups).effect;
+        draw_circle(center.x, center.y, pickup_radius, effect.color());
+        draw_circle_lines(center.x, center.y, pickup_radius, 3.0, WHITE);
+        let symbol = effect.symbol();
+        let symbol_dims = measure_text(symbol, Some(&assets.bold_font), pickup_radius as u16, 1.0);
+        draw_text_ex(
+            symbol,
+            center.x - symbol_dims.width / 2.0,
+            center.y + symbol_dims.offset_y / 2.0,
+            TextParams {
+                font: Some(&assets.bold_font),
+                font_size: pickup_radius as u16,
+                color: WHITE,
+                ..TextParams::default()
+            },
+        );
+    }
+}
+
+/// Draws the parallax layers back to front, then the ground strip. Each layer
+/// is offset by the distance run times its scroll factor, so it keeps pace
+/// with the run's speed.
+fn draw_background(world: &World, alpha: f32, assets: &Assets, screen_width: f32, screen_height: f32) {
+    let distance = world.previous_distance + (world.distance - world.previous_distance) * alpha;
+    let background = &world.tuning().background;
+    let floor_y = world.floor_y_position;
+
+    // A hot reload can change the layer list before the run picks it up.
+    for (layer, texture) in background.layers.iter().zip(&assets.background_layers) {
+        let size = Vec2::new(texture.width(), texture.height()) * layer.scale;
+        let y = layer.offset_y + match layer.anchor {
+            LayerAnchor::Top => 0.0,
+            LayerAnchor::Floor => floor_y - size.y,
+            LayerAnchor::Bottom => screen_height - size.y,
+        };
+        let scrolled = distance * layer.scroll_factor;
+        if layer.tile {
+            draw_tiled(texture, scrolled, y, size, screen_width, Color::from(layer.tint));
+        } else {
+            draw_texture_ex(
+                texture,
+                (screen_width - size.x) / 2.0 - scrolled,
+                y,
+                Color::from(layer.tint),
+                DrawTextureParams {
+                    dest_size: Some(size),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+
+    let ground = &background.ground;
+    let tile_size = Vec2::new(assets.ground.width(), assets.ground.height()) * ground.scale;
+    let fill_y = floor_y + tile_size.y;
+    draw_rectangle(0.0, fill_y, screen_width, screen_height - fill_y, Color::from(ground.fill_color));
+    draw_tiled(&assets.ground, distance, floor_y, tile_size, screen_width, Color::from(ground.tint));
+}
+
+/// Repeats `texture` at `size` in a row across the screen at `y`, shifted
+/// left by `scrolled` pixels.
+fn draw_tiled(texture: &Texture2D, scrolled: f32, y: f32, size: Vec2, screen_width: f32, tint: Color) {
+    let mut x = -scrolled.rem_euclid(size.x);
+    while x < screen_width {
+        draw_texture_ex(
+            texture,
+            x,
+            y,
+            tint,
+            DrawTextureParams {
+                dest_size: Some(size),
+                ..Default::default()
+            },
+        );
+        x += size.x;
+    }
+}
+
+/// Draws the star texture centred on `center`.
+pub fn draw_star(assets: &Assets, center: Vec2, size: f32, tint: Color) {
+    draw_texture_ex(
+        &assets.star,
+        center.x - size / 2.0,
+        center.y - size / 2.0,
+        tint,
+        DrawTextureParams {
+            dest_size: Some(Vec2::splat(size)),
+            ..Default::default()
+        },
+    );
+}
+
+/// Outlines the player's collision circle and every obstacle's collision
+/// shape, moved to the same interpolated positions as the sprites.
+pub fn draw_hitboxes(world: &World, alpha: f32) {
+    let color = config::debug::HITBOX_COLOR;
+    let thickness = config::debug::HITBOX_THICKNESS;
+
+    let player_hitbox = world.player_hitbox();
+    let player_offset = (world.previous_player_y_position - world.player_y_position) * (1.0 - alpha);
+    draw_circle_lines(
+        player_hitbox.center.x,
+        player_hitbox.center.y + player_offset,
+        player_hitbox.radius,
+        thickness,
+        color,
+    );
+
+    for obstacle in &world.obstacles {
+        let offset = (obstacle.previous_x - obstacle.rect.x) * (1.0 - alpha);
+        match world.obstacle_hitbox(obstacle) {
+            Shape::Rects(rects) => {
+                for rect in rects {
+                    draw_rectangle_lines(rect.x + offset, rect.y, rect.w, rect.h, thickness, color);
+                }
+            }
+            Shape::Polygon(points) => {
+                for (start, end) in points.iter().zip(points.iter().cycle().skip(1)) {
+                    draw_line(start.x + offset, start.y, end.x + offset, end.y, thickness, color);
+                }
+            }
+        }
+    }
+}
+
+/// Draws `texture` at `size` scaled by the frame, keeping the bottom centre
+/// on `feet`, so squashes stay planted on the ground.
+fn draw_sprite(texture: &Texture2D, feet: Vec2, size: Vec2, frame: &AnimationFrame, tint: Color) {
+    let draw_size = size * frame.scale;
+    draw_texture_ex(
+        texture,
+        feet.x - draw_size.x / 2.0,
+        feet.y - draw_size.y,
+        tint,
+        DrawTextureParams {
+            dest_size: Some(draw_size),
+            source: frame.source,
+            ..Default::default()
+        },
+    );
+}
+
+/// Draws the soot sprite standing on `player_y_position`, its feet line.
+pub fn draw_player(
+    assets: &Assets,
+    player: &PlayerTuning,
+    x_offset: f32,
+    player_y_position: f32,
+    frame: &AnimationFrame,
+    tint: Color,
+) {
+    let desired_player_collision_height = player.radius * 2.0;
+    let frame_height = frame.source.map_or(assets.player.height(), |source| source.h);
+    let frame_width = frame.source.map_or(assets.player.width(), |source| source.w);
+    let player_display_scale = desired_player_collision_height / frame_height;
+    let player_display_width = frame_width * player_display_scale;
+
+    draw_sprite(
+        &assets.player,
+        Vec2::new(player.x_position + x_offset, player_y_position),
+        Vec2::new(player_display_width, desired_player_collision_height),
+        frame,
+        tint,
+    );
+}
+
+/// Draws the title, an optional mode line under it, the stars collected,
+/// a timer bar per active power-up and the score.
+pub fn draw_hud(
+    world: &World,
+    subtitle: Option<&str>,
+    tuning: &Tuning,
+    assets: &Assets,
+    screen_width: f32,
+) {
+    let text_params = TextParams {
+        font: Some(&assets.bold_font),
+        font_size: 30.0 as u16,
+        color: BLACK,
+        ..TextParams::default()
+    };
+
+    draw_text_ex(
+        "SOOT SPRINT",
+        20.0,
+        50.0,
+        text_params,
+    );
+
+    if let Some(subtitle) = subtitle {
+        draw_text_ex(
+            subtitle,
+            20.0,
+            80.0,
+            TextParams {
+                font: Some(&assets.bold_font),
+                font_size: 20,
+                color: DARKGRAY,
+                ..TextParams::default()
+            },
+        );
+    }
+
+    let icon_size = config::stars::HUD_ICON_SIZE;
+    let stars_y = 80.0 + icon_size;
+    draw_star(assets, Vec2::new(20.0 + icon_size / 2.0, stars_y - icon_size / 2.0), icon_size, WHITE);
+    draw_text_ex(
+        &std::format!("x {}", world.stars_collected),
+        28.0 + icon_size,
+        stars_y - 4.0,
+        TextParams {
+            font: Some(&assets.bold_font),
+            font_size: 22,
+            color: BLACK,
+            ..TextParams::default()
+        },
+    );
+
+    for (row, power_up) in world.power_ups.iter().enumerate() {
+        let row_y = stars_y + (row + 1) as f32 * config::power_ups::HUD_ROW_HEIGHT;
+        draw_text_ex(
+            &power_up.effect.label(),
+            20.0,
+            row_y,
+            TextParams {
+                font: Some(&assets.bold_font),
+                font_size: config::power_ups::HUD_FONT_SIZE as u16,
+                color: power_up.effect.color(),
+                ..TextParams::default()
+            },
+        );
+        let bar_x = 20.0 + config::power_ups::HUD_BAR_WIDTH;
+        let bar_y = row_y - config::power_ups::HUD_BAR_HEIGHT;
+        let remaining = (power_up.remaining_s / power_up.duration_s).clamp(0.0, 1.0);
+        draw_rectangle(
+            bar_x,
+            bar_y,
+            config::power_ups::HUD_BAR_WIDTH,
+            config::power_ups::HUD_BAR_HEIGHT,
+            LIGHTGRAY,
+        );
+        draw_rectangle(
+            bar_x,
+            bar_y,
+            config::power_ups::HUD_BAR_WIDTH * remaining,
+            config::power_ups::HUD_BAR_HEIGHT,
+            power_up.effect.color(),
+        );
+        draw_text_ex(
+            &std::format!("{:.1}s", power_up.remaining_s.max(0.0)),
+            bar_x + config::power_ups::HUD_BAR_WIDTH + 8.0,
+            row_y,
+            TextParams {
+                font: Some(&assets.bold_font),
+                font_size: config::power_ups::HUD_FONT_SIZE as u16,
+                color: DARKGRAY,
+                ..TextParams::default()
+            },
+        );
+    }
+
+    let score_text = std::format!("SCORE: {}", world.score);
+    let text_dimensions = measure_text(
+        &score_text,
+        None,
+        tuning.playing_ui.score_font_size as u16,
+        1.0,
+    );
+    let text_x = screen_width - text_dimensions.width - tuning.playing_ui.score_margin;
+    let text_y = tuning.playing_ui.score_margin + tuning.playing_ui.score_font_size;
+
+    let score_text_params = TextParams {
+        font: Some(&assets.bold_font),
+        font_size: 24.0 as u16,
+        color: BLACK,
+        ..TextParams::default()
+    };
+    draw_text_ex(
+        &score_text,
+        text_x,
+        text_y,
+        score_text_params,
+    );
+}
@@ -0,0 +1,50 @@
+/// Something the player wants to do, independent of the key, button or
+/// tap that triggered it. Stages only ever ask about actions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Jump,
+    FastFall,
+    Confirm,
+    Back,
+    Pause,
+    WatchReplay,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Action {
+    pub const COUNT: usize = 10;
+    pub const ALL: [Action; Action::COUNT] = [
+        Action::Jump,
+        Action::FastFall,
+        Action::Confirm,
+        Action::Back,
+        Action::Pause,
+        Action::WatchReplay,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+    ];
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Jump => "Jump",
+            Action::FastFall => "Fast Fall",
+            Action::Confirm => "Confirm",
+            Action::Back => "Back",
+            Action::Pause => "Pause",
+            Action::WatchReplay => "Replay",
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+        }
+    }
+}
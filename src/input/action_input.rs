@@ -0,0 +1,86 @@
+use macroquad::prelude::*;
+use super::action::Action;
+use super::bindings::{Binding, Bindings};
+use super::gamepad::Gamepads;
+use crate::virtual_screen::VirtualScreen;
+
+/// The actions triggered this frame, read once per frame from the
+/// keyboard, mouse, gamepads and touch screen through the player's bindings.
+pub struct ActionInput {
+    pressed: [bool; Action::COUNT],
+    down: [bool; Action::COUNT],
+    /// Where a left click or a new touch landed this frame, like `pointer`
+    /// on the virtual screen.
+    pub tap: Option<Vec2>,
+    pub pointer: Vec2,
+    pub pointer_moved: bool,
+    /// The first bindable key or button pressed this frame, for remapping.
+    pub new_binding: Option<Binding>,
+}
+
+impl ActionInput {
+    /// Expects `simulate_mouse_with_touch(false)`, so a tap is not also
+    /// reported as a click. Positions are mapped from the window to
+    /// `virtual_screen`. `gamepads` must already be updated for this frame.
+    pub fn poll(bindings: &Bindings, virtual_screen: &VirtualScreen, gamepads: &Gamepads) -> Self {
+        let touches = touches();
+        let new_touch = touches
+            .iter()
+            .find(|touch| touch.phase == TouchPhase::Started)
+            .map(|touch| virtual_screen.to_virtual(touch.position));
+        let is_touching = touches
+            .iter()
+            .any(|touch| !matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled));
+
+        let is_pressed = |binding: &Binding| match *binding {
+            Binding::Key(key) => is_key_pressed(key),
+            Binding::Mouse(button) => is_mouse_button_pressed(button),
+            Binding::Gamepad(button) => gamepads.is_pressed(button),
+            Binding::Tap => new_touch.is_some(),
+        };
+        let is_down = |binding: &Binding| match *binding {
+            Binding::Key(key) => is_key_down(key),
+            Binding::Mouse(button) => is_mouse_button_down(button),
+            Binding::Gamepad(button) => gamepads.is_down(button),
+            Binding::Tap => is_touching,
+        };
+        let mut pressed = [false; Action::COUNT];
+        let mut down = [false; Action::COUNT];
+        for action in Action::ALL {
+            pressed[action.index()] = bindings.get(action).iter().any(is_pressed);
+            down[action.index()] = bindings.get(action).iter().any(is_down);
+        }
+
+        let (mouse_x, mouse_y) = mouse_position();
+        let mouse = virtual_screen.to_virtual(Vec2::new(mouse_x, mouse_y));
+        let tap = new_touch.or_else(|| is_mouse_button_pressed(MouseButton::Left).then_some(mouse));
+        let new_binding = get_last_key_pressed()
+            .and_then(Binding::key)
+            .or_else(|| {
+                [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+                    .into_iter()
+                    .find(|&button| is_mouse_button_pressed(button))
+                    .map(Binding::Mouse)
+            })
+            .or_else(|| gamepads.first_pressed().map(Binding::Gamepad));
+
+        Self {
+            pressed,
+            down,
+            tap,
+            pointer: new_touch.unwrap_or(mouse),
+            pointer_moved: mouse_delta_position() != Vec2::ZERO,
+            new_binding,
+        }
+    }
+
+    /// Whether `action` was triggered this frame.
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.pressed[action.index()]
+    }
+
+    /// Whether one of `action`'s bindings is held.
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down[action.index()]
+    }
+}
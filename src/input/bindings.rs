@@ -0,0 +1,306 @@
+use macroquad::input::{KeyCode, MouseButton};
+use serde::{Deserialize, Deserializer, Serialize};
+use super::action::Action;
+use super::gamepad::GamepadButton;
+
+/// Keys that can be bound and written to the save file by name.
+const NAMED_KEYS: &[KeyCode] = &[
+    KeyCode::Space, KeyCode::Enter, KeyCode::Escape, KeyCode::Tab, KeyCode::Backspace,
+    KeyCode::Up, KeyCode::Down, KeyCode::Left, KeyCode::Right,
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G,
+    KeyCode::H, KeyCode::I, KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N,
+    KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R, KeyCode::S, KeyCode::T, KeyCode::U,
+    KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4,
+    KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9, KeyCode::KpEnter,
+    KeyCode::LeftShift, KeyCode::RightShift, KeyCode::LeftControl, KeyCode::RightControl,
+    KeyCode::LeftAlt, KeyCode::RightAlt, KeyCode::Comma, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Semicolon, KeyCode::Apostrophe, KeyCode::Minus, KeyCode::Equal,
+    KeyCode::Insert, KeyCode::Delete, KeyCode::Home, KeyCode::End,
+    KeyCode::PageUp, KeyCode::PageDown,
+    KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8,
+    // The Android back button.
+    KeyCode::Back,
+];
+
+const NAMED_MOUSE_BUTTONS: [(MouseButton, &str); 3] = [
+    (MouseButton::Left, "MouseLeft"),
+    (MouseButton::Right, "MouseRight"),
+    (MouseButton::Middle, "MouseMiddle"),
+];
+
+/// Actions read on the same screen, which must not share a binding. Jump
+/// and Confirm may share one since both restart on Game Over.
+const SCREEN_ACTIONS: [&[Action]; 3] = [
+    // A run.
+    &[Action::Jump, Action::FastFall, Action::Pause, Action::Back],
+    // Menus and replays.
+    &[
+        Action::Confirm, Action::Back, Action::Pause, Action::WatchReplay,
+        Action::Up, Action::Down, Action::Left, Action::Right,
+    ],
+    // Game Over.
+    &[Action::Jump, Action::WatchReplay],
+];
+
+/// One way of triggering an action. Saved by name, such as `"Space"`,
+/// `"MouseLeft"`, `"PadSouth"` or `"Tap"`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+    /// A finger touching the screen anywhere.
+    Tap,
+}
+
+impl Binding {
+    /// Binds `key` if it is one that can be saved.
+    pub fn key(key: KeyCode) -> Option<Self> {
+        NAMED_KEYS.contains(&key).then_some(Binding::Key(key))
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            Binding::Key(key) => std::format!("{:?}", key),
+            Binding::Mouse(button) => NAMED_MOUSE_BUTTONS
+                .iter()
+                .find(|(named, _)| *named == button)
+                .map_or("MouseUnknown", |(_, name)| name)
+                .to_string(),
+            Binding::Gamepad(button) => button.name().to_string(),
+            Binding::Tap => "Tap".to_string(),
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name == "Tap" {
+            return Some(Binding::Tap);
+        }
+        if let Some((button, _)) = NAMED_MOUSE_BUTTONS.iter().find(|(_, named)| *named == name) {
+            return Some(Binding::Mouse(*button));
+        }
+        if let Some(button) = GamepadButton::ALL.into_iter().find(|button| button.name() == name) {
+            return Some(Binding::Gamepad(button));
+        }
+        NAMED_KEYS
+            .iter()
+            .find(|key| std::format!("{:?}", key) == name)
+            .map(|&key| Binding::Key(key))
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Binding::from_name(&name).ok_or_else(|| std::format!("unknown input binding {:?}", name))
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.name()
+    }
+}
+
+/// The bindings of every action, saved with the settings. An action left
+/// out of the save file keeps its default bindings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bindings {
+    #[serde(deserialize_with = "known_bindings")]
+    pub jump: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub fast_fall: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub confirm: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub back: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub pause: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub watch_replay: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub up: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub down: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub left: Vec<Binding>,
+    #[serde(deserialize_with = "known_bindings")]
+    pub right: Vec<Binding>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            jump: vec![
+                Binding::Key(KeyCode::Space),
+                Binding::Mouse(MouseButton::Left),
+                Binding::Gamepad(GamepadButton::South),
+                Binding::Tap,
+            ],
+            fast_fall: vec![Binding::Key(KeyCode::Down), Binding::Gamepad(GamepadButton::DPadDown)],
+            confirm: vec![
+                Binding::Key(KeyCode::Enter),
+                Binding::Key(KeyCode::KpEnter),
+                Binding::Gamepad(GamepadButton::South),
+            ],
+            back: vec![
+                Binding::Key(KeyCode::Escape),
+                Binding::Key(KeyCode::Back),
+                Binding::Gamepad(GamepadButton::East),
+            ],
+            pause: vec![Binding::Key(KeyCode::P), Binding::Gamepad(GamepadButton::Start)],
+            watch_replay: vec![Binding::Key(KeyCode::R), Binding::Gamepad(GamepadButton::North)],
+            up: vec![Binding::Key(KeyCode::Up), Binding::Gamepad(GamepadButton::DPadUp)],
+            down: vec![Binding::Key(KeyCode::Down), Binding::Gamepad(GamepadButton::DPadDown)],
+            left: vec![Binding::Key(KeyCode::Left), Binding::Gamepad(GamepadButton::DPadLeft)],
+            right: vec![Binding::Key(KeyCode::Right), Binding::Gamepad(GamepadButton::DPadRight)],
+        }
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        match action {
+            Action::Jump => &self.jump,
+            Action::FastFall => &self.fast_fall,
+            Action::Confirm => &self.confirm,
+            Action::Back => &self.back,
+            Action::Pause => &self.pause,
+            Action::WatchReplay => &self.watch_replay,
+            Action::Up => &self.up,
+            Action::Down => &self.down,
+            Action::Left => &self.left,
+            Action::Right => &self.right,
+        }
+    }
+
+    fn get_mut(&mut self, action: Action) -> &mut Vec<Binding> {
+        match action {
+            Action::Jump => &mut self.jump,
+            Action::FastFall => &mut self.fast_fall,
+            Action::Confirm => &mut self.confirm,
+            Action::Back => &mut self.back,
+            Action::Pause => &mut self.pause,
+            Action::WatchReplay => &mut self.watch_replay,
+            Action::Up => &mut self.up,
+            Action::Down => &mut self.down,
+            Action::Left => &mut self.left,
+            Action::Right => &mut self.right,
+        }
+    }
+
+    /// Makes `binding` the only binding of its device for `action`: a new
+    /// key or mouse button replaces the keys and mouse buttons, a new
+    /// gamepad button replaces the gamepad buttons. A touch binding stays,
+    /// since a touch screen has nothing to rebind it to.
+    ///
+    /// Fails with the other action if one read on the same screen already
+    /// uses `binding`, leaving the bindings unchanged.
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> Result<(), Action> {
+        if let Some(clash) = SCREEN_ACTIONS
+            .iter()
+            .filter(|actions| actions.contains(&action))
+            .flat_map(|actions| actions.iter().copied())
+            .find(|&other| other != action && self.get(other).contains(&binding))
+        {
+            return Err(clash);
+        }
+        let is_gamepad = |binding: &Binding| matches!(binding, Binding::Gamepad(_));
+        let bindings = self.get_mut(action);
+        bindings.retain(|existing| *existing == Binding::Tap || is_gamepad(existing) != is_gamepad(&binding));
+        if !bindings.contains(&binding) {
+            bindings.insert(0, binding);
+        }
+        Ok(())
+    }
+
+    /// The key, mouse and gamepad bindings of `action` for on-screen
+    /// prompts, such as `"Space / MouseLeft / PadSouth"`.
+    pub fn label(&self, action: Action) -> String {
+        let names: Vec<String> = self
+            .get(action)
+            .iter()
+            .filter(|&&binding| binding != Binding::Tap)
+            .map(|binding| binding.name())
+            .collect();
+        if names.is_empty() {
+            "unbound".to_string()
+        } else {
+            names.join(" / ")
+        }
+    }
+}
+
+/// Reads a list of binding names, skipping any this build does not know
+/// so one stale name does not cost the player the rest of the save file.
+fn known_bindings<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Binding>, D::Error> {
+    let names = Vec::<String>::deserialize(deserializer)?;
+    Ok(names.iter().filter_map(|name| Binding::from_name(name)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bindings_are_saved_by_name_and_unknown_names_are_skipped() {
+        let mut bindings = Bindings::default();
+        bindings.rebind(Action::Jump, Binding::Key(KeyCode::W)).unwrap();
+        assert_eq!(
+            bindings.jump,
+            vec![Binding::Key(KeyCode::W), Binding::Gamepad(GamepadButton::South), Binding::Tap]
+        );
+        assert_eq!(bindings.label(Action::Jump), "W / PadSouth");
+        bindings.rebind(Action::Jump, Binding::Gamepad(GamepadButton::West)).unwrap();
+        assert_eq!(bindings.label(Action::Jump), "PadWest / W");
+
+        let json = serde_json::to_string(&bindings).unwrap();
+        assert!(json.contains(r#""jump":["PadWest","W","Tap"]"#));
+        assert_eq!(serde_json::from_str::<Bindings>(&json).unwrap(), bindings);
+
+        let loaded: Bindings = serde_json::from_str(r#"{"pause": ["Gamepad7", "MouseRight", "PadStart"]}"#).unwrap();
+        assert_eq!(
+            loaded.pause,
+            vec![Binding::Mouse(MouseButton::Right), Binding::Gamepad(GamepadButton::Start)]
+        );
+        assert_eq!(loaded.jump, Bindings::default().jump);
+        assert!(Binding::key(KeyCode::F2).is_none());
+    }
+
+    #[test]
+    fn default_bindings_never_clash_on_a_screen() {
+        let bindings = Bindings::default();
+        for actions in SCREEN_ACTIONS {
+            for (index, &action) in actions.iter().enumerate() {
+                for &other in &actions[index + 1..] {
+                    let shared: Vec<_> = bindings
+                        .get(action)
+                        .iter()
+                        .filter(|binding| bindings.get(other).contains(binding))
+                        .collect();
+                    assert!(shared.is_empty(), "{:?} and {:?} share {:?}", action, other, shared);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_binding_used_on_the_same_screen_is_rejected() {
+        let mut bindings = Bindings::default();
+        assert_eq!(bindings.rebind(Action::Jump, Binding::Key(KeyCode::Escape)), Err(Action::Back));
+        assert_eq!(bindings.rebind(Action::Confirm, Binding::Key(KeyCode::R)), Err(Action::WatchReplay));
+        assert_eq!(bindings, Bindings::default());
+
+        // Confirm is never read during a run, and FastFall never in a menu.
+        bindings.rebind(Action::Confirm, Binding::Key(KeyCode::Space)).unwrap();
+        bindings.rebind(Action::FastFall, Binding::Key(KeyCode::Up)).unwrap();
+        assert_eq!(bindings.confirm[0], Binding::Key(KeyCode::Space));
+        assert_eq!(bindings.fast_fall[0], Binding::Key(KeyCode::Up));
+    }
+}
@@ -0,0 +1,141 @@
+/// A gamepad button in the standard layout, named by position so the same
+/// binding works on any controller. Saved by name, such as `"PadSouth"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadButton {
+    /// A on Xbox layouts, Cross on PlayStation.
+    South,
+    East,
+    West,
+    North,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+impl GamepadButton {
+    pub const ALL: [GamepadButton; 14] = [
+        GamepadButton::South,
+        GamepadButton::East,
+        GamepadButton::West,
+        GamepadButton::North,
+        GamepadButton::LeftBumper,
+        GamepadButton::RightBumper,
+        GamepadButton::LeftTrigger,
+        GamepadButton::RightTrigger,
+        GamepadButton::Select,
+        GamepadButton::Start,
+        GamepadButton::DPadUp,
+        GamepadButton::DPadDown,
+        GamepadButton::DPadLeft,
+        GamepadButton::DPadRight,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            GamepadButton::South => "PadSouth",
+            GamepadButton::East => "PadEast",
+            GamepadButton::West => "PadWest",
+            GamepadButton::North => "PadNorth",
+            GamepadButton::LeftBumper => "PadLeftBumper",
+            GamepadButton::RightBumper => "PadRightBumper",
+            GamepadButton::LeftTrigger => "PadLeftTrigger",
+            GamepadButton::RightTrigger => "PadRightTrigger",
+            GamepadButton::Select => "PadSelect",
+            GamepadButton::Start => "PadStart",
+            GamepadButton::DPadUp => "PadUp",
+            GamepadButton::DPadDown => "PadDown",
+            GamepadButton::DPadLeft => "PadLeft",
+            GamepadButton::DPadRight => "PadRight",
+        }
+    }
+}
+
+/// The buttons of every connected gamepad, read once per frame. Built
+/// without the `gamepad` feature it never reports a press, so bindings to
+/// gamepad buttons stay in the save file but do nothing.
+pub struct Gamepads {
+    #[cfg(feature = "gamepad")]
+    gilrs: Option<gilrs::Gilrs>,
+    /// Buttons that went down this frame.
+    pressed: Vec<GamepadButton>,
+}
+
+impl Gamepads {
+    #[cfg(feature = "gamepad")]
+    pub fn new() -> Self {
+        let gilrs = gilrs::Gilrs::new()
+            .map_err(|e| macroquad::logging::warn!("Gamepads unavailable: {}", e))
+            .ok();
+        Self { gilrs, pressed: Vec::new() }
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    pub fn new() -> Self {
+        Self { pressed: Vec::new() }
+    }
+
+    /// Collects the presses since the previous frame.
+    pub fn update(&mut self) {
+        self.pressed.clear();
+        #[cfg(feature = "gamepad")]
+        if let Some(gilrs) = self.gilrs.as_mut() {
+            while let Some(event) = gilrs.next_event() {
+                if let gilrs::EventType::ButtonPressed(button, _) = event.event
+                    && let Some(button) = GamepadButton::ALL.into_iter().find(|pad| to_gilrs(*pad) == button)
+                {
+                    self.pressed.push(button);
+                }
+            }
+        }
+    }
+
+    /// Whether `button` went down on any gamepad this frame.
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    /// Whether `button` is held on any gamepad.
+    #[cfg(feature = "gamepad")]
+    pub fn is_down(&self, button: GamepadButton) -> bool {
+        self.gilrs
+            .as_ref()
+            .is_some_and(|gilrs| gilrs.gamepads().any(|(_, gamepad)| gamepad.is_pressed(to_gilrs(button))))
+    }
+
+    #[cfg(not(feature = "gamepad"))]
+    pub fn is_down(&self, _button: GamepadButton) -> bool {
+        false
+    }
+
+    /// The first button pressed this frame, for remapping.
+    pub fn first_pressed(&self) -> Option<GamepadButton> {
+        self.pressed.first().copied()
+    }
+}
+
+#[cfg(feature = "gamepad")]
+fn to_gilrs(button: GamepadButton) -> gilrs::Button {
+    match button {
+        GamepadButton::South => gilrs::Button::South,
+        GamepadButton::East => gilrs::Button::East,
+        GamepadButton::West => gilrs::Button::West,
+        GamepadButton::North => gilrs::Button::North,
+        GamepadButton::LeftBumper => gilrs::Button::LeftTrigger,
+        GamepadButton::RightBumper => gilrs::Button::RightTrigger,
+        GamepadButton::LeftTrigger => gilrs::Button::LeftTrigger2,
+        GamepadButton::RightTrigger => gilrs::Button::RightTrigger2,
+        GamepadButton::Select => gilrs::Button::Select,
+        GamepadButton::Start => gilrs::Button::Start,
+        GamepadButton::DPadUp => gilrs::Button::DPadUp,
+        GamepadButton::DPadDown => gilrs::Button::DPadDown,
+        GamepadButton::DPadLeft => gilrs::Button::DPadLeft,
+        GamepadButton::DPadRight => gilrs::Button::DPadRight,
+    }
+}
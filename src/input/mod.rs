@@ -0,0 +1,4 @@
+pub mod action;
+pub mod action_input;
+pub mod bindings;
+pub mod gamepad;
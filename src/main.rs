@@ -1,13 +1,47 @@
+mod animation;
 mod assets;
+mod audio;
 mod config;
+mod dev;
 mod game_stages;
+mod input;
+mod simulation;
 mod state;
+mod tuning;
 mod types;
+mod virtual_screen;
 
+use std::path::{Path, PathBuf};
 use macroquad::prelude::*;
 use assets::Assets;
-use game_stages::{GameStage, StageTransition, PersistentData};
+use audio::AudioPlayer;
+use config::display::{VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+use dev::{file_watcher::FileWatcher, toast::Toast};
+use game_stages::{GameStage, PersistentData};
 use game_stages::intro_stage::IntroStage;
+use game_stages::replay_stage::ReplayStage;
+use game_stages::stage_manager::StageManager;
+use input::action_input::ActionInput;
+use input::gamepad::Gamepads;
+use state::replay_file;
+use tuning::{Tuning, TUNING_PATH};
+use virtual_screen::VirtualScreen;
+
+/// `--replay <file>` opens a saved replay instead of the intro.
+fn replay_path_from_args() -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--replay" {
+            return args.next().map(PathBuf::from);
+        }
+    }
+    None
+}
+
+/// `--dev` reloads the tuning file and textures whenever they change.
+fn is_dev_mode() -> bool {
+    std::env::args().skip(1).any(|arg| arg == "--dev")
+}
 
 #[macroquad::main("SootSprint")]
 async fn main() {
@@ -32,8 +66,25 @@ async fn main() {
     );
 
     next_frame().await;
+    // Touches are read as taps by `ActionInput` rather than as clicks.
+    simulate_mouse_with_touch(false);
 
-    let assets = match Assets::load().await {
+    let mut tuning = match Tuning::load(Path::new(TUNING_PATH)) {
+        Ok(tuning) => tuning,
+        Err(e) => {
+            error!("Invalid tuning file {}: {}", TUNING_PATH, e);
+            let message = std::format!("Error in {}: {}", TUNING_PATH, e);
+            loop {
+                clear_background(RED);
+                for (index, line) in message.lines().enumerate() {
+                    draw_text(line, 20.0, screen_height() / 2.0 + index as f32 * 30.0, 30.0, BLACK);
+                }
+                next_frame().await;
+            }
+        }
+    };
+
+    let mut assets = match Assets::load(&tuning).await {
         Ok(loaded_assets) => loaded_assets,
         Err(e) => {
             error!("Failed to load assets: {}", e);
@@ -51,40 +102,103 @@ async fn main() {
         }
     };
 
-    let mut persistent_data = PersistentData::new();
-    let mut current_stage: Box<dyn GameStage> = Box::new(
-        IntroStage::new(
-            screen_width(),
-            screen_height(),
-        )
-    );
+    let mut persistent_data = PersistentData::load();
+    let mut audio = AudioPlayer::new(assets.sounds.clone(), persistent_data.settings.audio);
+    let first_stage: Box<dyn GameStage> = match replay_path_from_args() {
+        Some(path) => match replay_file::load_path(&path)
+            .and_then(|replay| replay.check_tuning(&tuning).map(|()| replay)) {
+            Ok(replay) => Box::new(
+                ReplayStage::new(
+                    replay,
+                    &tuning,
+                    VIRTUAL_WIDTH,
+                    VIRTUAL_HEIGHT,
+                )
+            ),
+            Err(e) => {
+                error!("Failed to load replay {}: {}", path.display(), e);
+                Box::new(IntroStage::new(&tuning, VIRTUAL_WIDTH, VIRTUAL_HEIGHT))
+            }
+        },
+        None => Box::new(
+            IntroStage::new(
+                &tuning,
+                VIRTUAL_WIDTH,
+                VIRTUAL_HEIGHT,
+            )
+        ),
+    };
+
+    let mut stage_manager = StageManager::new(first_stage, &mut persistent_data);
+    let mut file_watcher = is_dev_mode()
+        .then(|| FileWatcher::new(Path::new(TUNING_PATH), Path::new(config::dev::ASSETS_DIR)));
+    let mut toast = Toast::new();
+    let virtual_screen = VirtualScreen::new();
+    let mut gamepads = Gamepads::new();
 
     loop {
         let dt = get_frame_time();
-        let current_screen_width = screen_width();
-        let current_screen_height = screen_height();
+        if let Some(file_watcher) = file_watcher.as_mut() {
+            let changes = file_watcher.poll(dt);
+            // Reloading the tuning swaps the run's physics and drops its
+            // replay, so an image only does so when hitboxes were traced
+            // from it. Any other image just swaps the textures.
+            let retrace = changes.assets.iter().any(|path| tuning.traces_texture(path));
+            let mut reload_textures = !changes.assets.is_empty();
+            let mut tuning_failed = false;
+            if changes.tuning || retrace {
+                match Tuning::load(Path::new(TUNING_PATH)) {
+                    Ok(reloaded) => {
+                        tuning = reloaded;
+                        stage_manager.reload_tuning(&tuning);
+                        reload_textures = true;
+                    }
+                    Err(e) => {
+                        toast.show_error(std::format!("{}: {}", TUNING_PATH, e));
+                        tuning_failed = true;
+                    }
+                }
+            }
+            if reload_textures {
+                match assets.reload_textures(&tuning).await {
+                    // Keep the tuning error on screen.
+                    Ok(()) if tuning_failed => {}
+                    Ok(()) if changes.tuning => toast.show(std::format!("Reloaded {}", TUNING_PATH)),
+                    Ok(()) => toast.show("Reloaded textures".to_string()),
+                    Err(e) => toast.show_error(std::format!("Texture reload failed: {}", e)),
+                }
+            }
+        }
+        toast.update(dt);
+        audio.set_settings(persistent_data.settings.audio);
+        gamepads.update();
+        let input = ActionInput::poll(&persistent_data.settings.controls, &virtual_screen, &gamepads);
 
-        let transition = current_stage.update(
+        let keep_running = stage_manager.update(
             dt,
+            &input,
             &mut persistent_data,
-            current_screen_width,
-            current_screen_height,
+            &mut audio,
+            &tuning,
+            VIRTUAL_WIDTH,
+            VIRTUAL_HEIGHT,
         );
-
-        match transition {
-            StageTransition::Switch(new_stage_box) => {
-                current_stage = new_stage_box;
-            }
-            StageTransition::None => {}
+        if !keep_running {
+            break;
         }
+        audio.play_music(stage_manager.music());
 
-        current_stage.draw(
+        virtual_screen.begin();
+        stage_manager.draw(
             &assets,
             &persistent_data,
-            current_screen_width,
-            current_screen_height,
+            &tuning,
+            VIRTUAL_WIDTH,
+            VIRTUAL_HEIGHT,
         );
+        toast.draw(VIRTUAL_HEIGHT);
+        virtual_screen.present();
 
         next_frame().await;
     }
-}
\ No newline at end of file
+}
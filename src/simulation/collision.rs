@@ -0,0 +1,140 @@
+use macroquad::math::{Rect, Vec2};
+
+/// The player's hitbox, which matches the round sprite better than a rect.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Circle {
+    pub center: Vec2,
+    pub radius: f32,
+}
+
+/// An obstacle's hitbox in world coordinates.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Rects(Vec<Rect>),
+    /// Convex or concave, points in order around the outline.
+    Polygon(Vec<Vec2>),
+}
+
+impl Circle {
+    pub fn overlaps(&self, shape: &Shape) -> bool {
+        match shape {
+            Shape::Rects(rects) => rects.iter().any(|rect| self.overlaps_rect(rect)),
+            Shape::Polygon(points) => self.overlaps_polygon(points),
+        }
+    }
+
+    pub fn overlaps_circle(&self, other: &Circle) -> bool {
+        let reach = self.radius + other.radius;
+        self.center.distance_squared(other.center) <= reach * reach
+    }
+
+    pub fn overlaps_rect(&self, rect: &Rect) -> bool {
+        let closest = Vec2::new(
+            self.center.x.clamp(rect.left(), rect.right()),
+            self.center.y.clamp(rect.top(), rect.bottom()),
+        );
+        closest.distance_squared(self.center) <= self.radius * self.radius
+    }
+
+    pub fn overlaps_polygon(&self, points: &[Vec2]) -> bool {
+        if points.len() < 3 {
+            return false;
+        }
+        let radius_squared = self.radius * self.radius;
+        let edges = points.iter().zip(points.iter().cycle().skip(1));
+        let mut is_inside = false;
+        for (&start, &end) in edges {
+            if distance_squared_to_segment(self.center, start, end) <= radius_squared {
+                return true;
+            }
+            // Even-odd ray cast to the right of the centre.
+            if (start.y > self.center.y) != (end.y > self.center.y) {
+                let crossing_x = start.x + (self.center.y - start.y) / (end.y - start.y) * (end.x - start.x);
+                if self.center.x < crossing_x {
+                    is_inside = !is_inside;
+                }
+            }
+        }
+        is_inside
+    }
+}
+
+fn distance_squared_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    let t = if length_squared > 0.0 {
+        ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    (start + segment * t).distance_squared(point)
+}
+
+/// Covers the opaque pixels of an RGBA image with one rect per horizontal
+/// band, in the image's own pixels as `[x, y, width, height]`. Fewer bands
+/// are cheaper to test, more hug the silhouette closer.
+pub fn rects_from_alpha_mask(width: usize, height: usize, rgba: &[u8], bands: usize) -> Vec<[f32; 4]> {
+    const OPAQUE_ALPHA: u8 = 128;
+
+    let is_opaque = |x: usize, y: usize| rgba[(y * width + x) * 4 + 3] >= OPAQUE_ALPHA;
+    let band_count = bands.clamp(1, height.max(1));
+    let mut rects = Vec::new();
+    for band in 0..band_count {
+        let band_top = band * height / band_count;
+        let band_bottom = (band + 1) * height / band_count;
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for y in band_top..band_bottom {
+            for x in 0..width {
+                if is_opaque(x, y) {
+                    let (left, top, right, bottom) = bounds.unwrap_or((x, y, x, y));
+                    bounds = Some((left.min(x), top.min(y), right.max(x), bottom.max(y)));
+                }
+            }
+        }
+        if let Some((left, top, right, bottom)) = bounds {
+            rects.push([
+                left as f32,
+                top as f32,
+                (right - left + 1) as f32,
+                (bottom - top + 1) as f32,
+            ]);
+        }
+    }
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_misses_the_corner_a_rect_would_hit() {
+        let rect = Rect::new(100.0, 100.0, 50.0, 50.0);
+        let circle = Circle { center: Vec2::new(80.0, 80.0), radius: 25.0 };
+        let circle_bounds = Rect::new(55.0, 55.0, 50.0, 50.0);
+        assert!(circle_bounds.overlaps(&rect));
+        assert!(!circle.overlaps_rect(&rect));
+        assert!(Circle { radius: 30.0, ..circle }.overlaps_rect(&rect));
+    }
+
+    #[test]
+    fn circle_hits_polygon_edges_and_interior() {
+        let triangle = vec![Vec2::new(0.0, 100.0), Vec2::new(50.0, 0.0), Vec2::new(100.0, 100.0)];
+        let inside = Circle { center: Vec2::new(50.0, 70.0), radius: 1.0 };
+        let touching_edge = Circle { center: Vec2::new(50.0, 110.0), radius: 12.0 };
+        let beside_the_slope = Circle { center: Vec2::new(10.0, 20.0), radius: 10.0 };
+        assert!(inside.overlaps_polygon(&triangle));
+        assert!(touching_edge.overlaps_polygon(&triangle));
+        assert!(!beside_the_slope.overlaps_polygon(&triangle));
+    }
+
+    #[test]
+    fn alpha_mask_rects_skip_transparent_pixels() {
+        // 4x4 image with an opaque 2x2 block in the bottom-right corner.
+        let mut rgba = vec![0u8; 4 * 4 * 4];
+        for (x, y) in [(2, 2), (3, 2), (2, 3), (3, 3)] {
+            rgba[(y * 4 + x) * 4 + 3] = 255;
+        }
+        assert_eq!(rects_from_alpha_mask(4, 4, &rgba, 2), vec![[2.0, 2.0, 2.0, 2.0]]);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::config;
+use crate::tuning::PlayerTuning;
+use crate::types::obstacle::Obstacle;
+use super::world::World;
+
+/// Upper bound on the steps a check looks ahead, in case the course never
+/// scrolls the obstacles past the player.
+const MAX_CHECK_TICKS: usize = 60 * 60 * 10;
+
+/// Player positions on each step after a jump from the floor, integrated
+/// the same way as `World::step` and ending on the landing step.
+pub fn jump_arc(player: &PlayerTuning, floor_y_position: f32, dt: f32) -> Vec<f32> {
+    let player_height = player.radius * 2.0;
+    let mut velocity_y = player.jump_force;
+    let mut y_position = floor_y_position;
+    let mut arc = Vec::new();
+    while arc.len() < MAX_CHECK_TICKS {
+        velocity_y += player.gravity * dt;
+        y_position += velocity_y * dt;
+        if y_position < player_height {
+            y_position = player_height;
+            velocity_y = 0.0;
+        }
+        if y_position >= floor_y_position {
+            arc.push(floor_y_position);
+            break;
+        }
+        arc.push(y_position);
+    }
+    arc
+}
+
+/// Whether some timing of jumps gets a player starting on the floor past
+/// every obstacle in `obstacles` without touching one. The course moves
+/// left by `scroll_at(tick)` pixels on each step, and hitboxes come from
+/// `world`'s tuning with the player's grown by `margin`.
+///
+/// Jumps are only possible from the floor, so the search just tracks the
+/// steps on which the player can be standing: from each one it either
+/// waits a step or follows the whole arc to the landing step.
+pub fn is_clearable(
+    world: &World,
+    obstacles: &[Obstacle],
+    margin: f32,
+    scroll_at: impl Fn(usize) -> f32,
+) -> bool {
+    let player = &world.tuning().player;
+    let floor_y_position = world.floor_y_position;
+    let arc = jump_arc(player, floor_y_position, config::FIXED_TIMESTEP);
+    let player_left = player.x_position - player.hitbox_radius - margin;
+    let player_right = player.x_position + player.hitbox_radius + margin;
+
+    let mut sorted: Vec<&Obstacle> = obstacles.iter().collect();
+    sorted.sort_by(|a, b| a.rect.x.total_cmp(&b.rect.x));
+    let widest = sorted.iter().map(|obstacle| obstacle.rect.w).fold(0.0, f32::max);
+    let last_right = sorted.iter().map(|obstacle| obstacle.rect.right()).fold(f32::MIN, f32::max);
+
+    // How far the course has scrolled after each step, up to the first
+    // step on which every obstacle is behind the player.
+    let mut offsets = vec![0.0];
+    while let Some(&offset) = offsets.last()
+        && last_right - offset >= player_left
+        && offsets.len() < MAX_CHECK_TICKS {
+        offsets.push(offset + scroll_at(offsets.len()));
+    }
+    let horizon = offsets.len() - 1;
+
+    let collides = |tick: usize, y_position: f32| {
+        let Some(&offset) = offsets.get(tick) else {
+            return false;
+        };
+        let mut hitbox = world.player_hitbox_at(y_position);
+        hitbox.radius += margin;
+        // Only obstacles overlapping the player's column can touch it.
+        let first = sorted.partition_point(|obstacle| obstacle.rect.x - offset < player_left - widest);
+        let end = sorted.partition_point(|obstacle| obstacle.rect.x - offset <= player_right);
+        sorted[first..end.max(first)].iter().any(|obstacle| {
+            let mut rect = obstacle.rect;
+            rect.x -= offset;
+            let moved = Obstacle { rect, kind: obstacle.kind, previous_x: rect.x };
+            hitbox.overlaps(&world.obstacle_hitbox(&moved))
+        })
+    };
+
+    let mut on_floor = vec![false; horizon + arc.len() + 1];
+    on_floor[0] = !collides(0, floor_y_position);
+    for tick in 0..horizon {
+        if !on_floor[tick] {
+            continue;
+        }
+        if !collides(tick + 1, floor_y_position) {
+            on_floor[tick + 1] = true;
+        }
+        let clears_jump = arc
+            .iter()
+            .enumerate()
+            .all(|(step, &y_position)| !collides(tick + 1 + step, y_position));
+        if clears_jump {
+            on_floor[tick + arc.len()] = true;
+        }
+    }
+    on_floor[horizon..].iter().any(|&standing| standing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::sim_input::SimInput;
+    use std::path::Path;
+    use crate::tuning::{Tuning, TUNING_PATH};
+    use crate::types::difficulty::Difficulty;
+
+    const SCREEN_WIDTH: f32 = 800.0;
+    const FLOOR_Y: f32 = 390.0;
+
+    #[test]
+    fn single_obstacles_pass_and_walls_do_not() {
+        let world = World::new(SCREEN_WIDTH, FLOOR_Y, 1, Difficulty::Normal, &Tuning::default());
+        let scroll = |_| 300.0 * config::FIXED_TIMESTEP;
+        let rock = Obstacle::new(SCREEN_WIDTH, FLOOR_Y - 60.0, 80.0, 60.0, 0);
+        assert!(is_clearable(&world, &[rock], 0.0, scroll));
+
+        let wall = Obstacle::new(SCREEN_WIDTH, FLOOR_Y - 400.0, 80.0, 400.0, 0);
+        let mut tall_tuning = Tuning::default();
+        tall_tuning.obstacles.kinds[0].size = [80.0, 400.0];
+        tall_tuning.obstacles.kinds[0].hitbox_size = None;
+        tall_tuning.obstacles.kinds[0].shape = Default::default();
+        let tall_world = World::new(SCREEN_WIDTH, FLOOR_Y, 1, Difficulty::Normal, &tall_tuning);
+        assert!(!is_clearable(&tall_world, &[wall], 0.0, scroll));
+    }
+
+    /// Generates whole courses with collisions ignored, then searches each
+    /// one for a jump timing that clears it from start to finish. One
+    /// kind is a log too long for any jump, which the spawner must hold back.
+    #[test]
+    fn every_generated_course_is_beatable() {
+        let mut tuning = Tuning::load(Path::new(TUNING_PATH)).unwrap();
+        tuning.obstacles.kinds[2].size = [600.0, 60.0];
+        tuning.obstacles.kinds[2].hitbox_size = None;
+        tuning.obstacles.kinds[2].shape = Default::default();
+        for difficulty in Difficulty::ALL {
+            for seed in 0..12u64 {
+                let start_distance = (seed % 3) as f32 * 1_200_000.0;
+                let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, seed, difficulty, &tuning);
+                world.distance = start_distance;
+                let mut distances = vec![world.distance];
+                // Obstacles in course coordinates, where screen x is
+                // `rect.x - distance`.
+                let mut course = Vec::new();
+                for _ in 0..60 * 40 {
+                    world.step(SimInput::default(), config::FIXED_TIMESTEP);
+                    world.is_over = false;
+                    distances.push(world.distance);
+                    for obstacle in &world.obstacles {
+                        if obstacle.previous_x == world.spawn_x_position {
+                            let mut rect = obstacle.rect;
+                            rect.x += world.distance - start_distance;
+                            course.push(Obstacle::new(rect.x, rect.y, rect.w, rect.h, obstacle.kind));
+                        }
+                    }
+                }
+                assert!(course.len() > 10, "{:?} seed {} spawned too little", difficulty, seed);
+
+                let last_scroll = distances[distances.len() - 1] - distances[distances.len() - 2];
+                let scroll_at = |tick: usize| match distances.get(tick) {
+                    Some(now) if tick > 0 => now - distances[tick - 1],
+                    _ => last_scroll,
+                };
+                assert!(
+                    is_clearable(&world, &course, 0.0, scroll_at),
+                    "{:?} seed {} generated an unbeatable course",
+                    difficulty,
+                    seed,
+                );
+                assert!(world.rejected_spawns > 0);
+                assert!(course.iter().all(|obstacle| obstacle.kind != 2));
+            }
+        }
+    }
+}
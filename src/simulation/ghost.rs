@@ -0,0 +1,100 @@
+use crate::config;
+use crate::tuning::Tuning;
+use super::replay::Replay;
+use super::world::World;
+
+/// A recorded run re-simulated in lockstep with the live one, so the
+/// player can race their best attempt on the same seeded course.
+pub struct Ghost {
+    replay: Replay,
+    world: World,
+    tick: u32,
+}
+
+impl Ghost {
+    pub fn new(replay: Replay, tuning: &Tuning) -> Self {
+        let world = World::new(
+            replay.spawn_x_position,
+            replay.floor_y_position,
+            replay.seed,
+            replay.difficulty,
+            tuning,
+        );
+        Self {
+            replay,
+            world,
+            tick: 0,
+        }
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.world.is_over || self.tick >= self.replay.total_ticks
+    }
+
+    /// Advances the ghost by one fixed step; call once per live world step.
+    pub fn step(&mut self) {
+        if self.is_finished() {
+            return;
+        }
+        let input = self.replay.input_at(self.tick);
+        self.world.step(input, config::FIXED_TIMESTEP);
+        self.tick += 1;
+    }
+
+    /// Positive when `score` is past the ghost's score at the same tick,
+    /// or its final score once the ghost's run is over.
+    pub fn lead_over(&self, score: u32) -> i64 {
+        let ghost_score = if self.is_finished() {
+            self.replay.final_score
+        } else {
+            self.world.score
+        };
+        score as i64 - ghost_score as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{difficulty::Difficulty, run_mode::RunMode};
+
+    #[test]
+    fn the_lead_is_against_the_ghost_at_the_same_tick_until_it_finishes() {
+        let tuning = Tuning::default();
+        let replay = Replay {
+            run_mode: RunMode::Endless,
+            difficulty: Difficulty::Normal,
+            seed: 99,
+            spawn_x_position: 800.0,
+            floor_y_position: 390.0,
+            tuning_hash: tuning.simulation_hash(),
+            total_ticks: 60,
+            final_score: 10_000,
+            final_stars: 0,
+            jump_ticks: Vec::new(),
+            jump_hold_ticks: Vec::new(),
+            fast_fall_ticks: Vec::new(),
+        };
+        let mut ghost = Ghost::new(replay, &tuning);
+        for _ in 0..30 {
+            ghost.step();
+        }
+        assert!(!ghost.is_finished());
+        assert!(ghost.world().score > 0);
+        assert_eq!(ghost.lead_over(ghost.world().score), 0);
+
+        for _ in 0..30 {
+            ghost.step();
+        }
+        assert!(ghost.is_finished());
+        assert_eq!(ghost.lead_over(10_001), 1);
+    }
+}
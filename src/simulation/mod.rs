@@ -0,0 +1,9 @@
+pub mod collision;
+pub mod fairness;
+pub mod ghost;
+pub mod power_ups;
+pub mod replay;
+pub mod rng;
+pub mod sim_event;
+pub mod sim_input;
+pub mod world;
@@ -0,0 +1,228 @@
+use macroquad::color::Color;
+use crate::config;
+use crate::tuning::PowerUpTuning;
+use crate::types::power_up::PowerUpKind;
+
+/// What the active power-ups change about the run on a step. Every step
+/// starts from the default, a plain run, and each active effect adjusts it,
+/// so an expired effect needs no cleanup beyond being dropped.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Modifiers {
+    /// Multiplies the difficulty curve's scroll speed, down to no slower
+    /// than the curve's starting speed.
+    pub scroll_speed_scale: f32,
+    pub score_multiplier: u32,
+    /// Jumps allowed before landing again.
+    pub air_jumps: u32,
+    /// Stars within this distance fly to the player at `magnet_speed`.
+    pub magnet_radius: f32,
+    pub magnet_speed: f32,
+}
+
+impl Default for Modifiers {
+    fn default() -> Self {
+        Self {
+            scroll_speed_scale: 1.0,
+            score_multiplier: 1,
+            air_jumps: 0,
+            magnet_radius: 0.0,
+            magnet_speed: 0.0,
+        }
+    }
+}
+
+/// A timed effect given by a power-up pickup, along with how its pickup
+/// and HUD timer look. A new effect implements this trait, adds a
+/// `PowerUpKind` and is built in `create` from its `[power_ups]` values.
+pub trait PowerUpEffect {
+    fn kind(&self) -> PowerUpKind;
+
+    /// Name on the HUD timer.
+    fn label(&self) -> String;
+
+    /// Letter drawn on the pickup.
+    fn symbol(&self) -> &'static str;
+
+    /// Fills the pickup and the HUD timer.
+    fn color(&self) -> Color;
+
+    /// Adjusts the run while the effect is active.
+    fn modify(&self, _modifiers: &mut Modifiers) {}
+
+    /// Whether the effect takes a collision for the player. An effect that
+    /// does is used up.
+    fn absorbs_collision(&self) -> bool {
+        false
+    }
+}
+
+/// An effect with the run time it has left.
+pub struct ActivePowerUp {
+    pub effect: Box<dyn PowerUpEffect>,
+    pub remaining_s: f32,
+    pub duration_s: f32,
+}
+
+impl ActivePowerUp {
+    pub fn kind(&self) -> PowerUpKind {
+        self.effect.kind()
+    }
+}
+
+/// Builds the effect of `kind` with its values and duration from the tuning.
+pub fn create(kind: PowerUpKind, tuning: &PowerUpTuning) -> ActivePowerUp {
+    let (effect, duration_s): (Box<dyn PowerUpEffect>, f32) = match kind {
+        PowerUpKind::Shield => (Box::new(Shield), tuning.shield_duration_s),
+        PowerUpKind::DoubleJump => (
+            Box::new(DoubleJump { air_jumps: tuning.air_jumps }),
+            tuning.double_jump_duration_s,
+        ),
+        PowerUpKind::SlowMotion => (
+            Box::new(SlowMotion { speed_scale: tuning.slow_motion_speed_scale }),
+            tuning.slow_motion_duration_s,
+        ),
+        PowerUpKind::Magnet => (
+            Box::new(Magnet { radius: tuning.magnet_radius, speed: tuning.magnet_speed }),
+            tuning.magnet_duration_s,
+        ),
+        PowerUpKind::ScoreMultiplier => (
+            Box::new(ScoreMultiplier { multiplier: tuning.score_multiplier }),
+            tuning.score_multiplier_duration_s,
+        ),
+    };
+    ActivePowerUp {
+        effect,
+        remaining_s: duration_s,
+        duration_s,
+    }
+}
+
+struct Shield;
+
+impl PowerUpEffect for Shield {
+    fn kind(&self) -> PowerUpKind {
+        PowerUpKind::Shield
+    }
+
+    fn label(&self) -> String {
+        "SHIELD".to_string()
+    }
+
+    fn symbol(&self) -> &'static str {
+        "S"
+    }
+
+    fn color(&self) -> Color {
+        config::power_ups::SHIELD_COLOR
+    }
+
+    fn absorbs_collision(&self) -> bool {
+        true
+    }
+}
+
+struct DoubleJump {
+    air_jumps: u32,
+}
+
+impl PowerUpEffect for DoubleJump {
+    fn kind(&self) -> PowerUpKind {
+        PowerUpKind::DoubleJump
+    }
+
+    fn label(&self) -> String {
+        "DOUBLE JUMP".to_string()
+    }
+
+    fn symbol(&self) -> &'static str {
+        "J"
+    }
+
+    fn color(&self) -> Color {
+        config::power_ups::DOUBLE_JUMP_COLOR
+    }
+
+    fn modify(&self, modifiers: &mut Modifiers) {
+        modifiers.air_jumps += self.air_jumps;
+    }
+}
+
+struct SlowMotion {
+    speed_scale: f32,
+}
+
+impl PowerUpEffect for SlowMotion {
+    fn kind(&self) -> PowerUpKind {
+        PowerUpKind::SlowMotion
+    }
+
+    fn label(&self) -> String {
+        "SLOW-MO".to_string()
+    }
+
+    fn symbol(&self) -> &'static str {
+        "T"
+    }
+
+    fn color(&self) -> Color {
+        config::power_ups::SLOW_MOTION_COLOR
+    }
+
+    fn modify(&self, modifiers: &mut Modifiers) {
+        modifiers.scroll_speed_scale *= self.speed_scale;
+    }
+}
+
+struct Magnet {
+    radius: f32,
+    speed: f32,
+}
+
+impl PowerUpEffect for Magnet {
+    fn kind(&self) -> PowerUpKind {
+        PowerUpKind::Magnet
+    }
+
+    fn label(&self) -> String {
+        "MAGNET".to_string()
+    }
+
+    fn symbol(&self) -> &'static str {
+        "M"
+    }
+
+    fn color(&self) -> Color {
+        config::power_ups::MAGNET_COLOR
+    }
+
+    fn modify(&self, modifiers: &mut Modifiers) {
+        modifiers.magnet_radius = modifiers.magnet_radius.max(self.radius);
+        modifiers.magnet_speed = modifiers.magnet_speed.max(self.speed);
+    }
+}
+
+struct ScoreMultiplier {
+    multiplier: u32,
+}
+
+impl PowerUpEffect for ScoreMultiplier {
+    fn kind(&self) -> PowerUpKind {
+        PowerUpKind::ScoreMultiplier
+    }
+
+    fn label(&self) -> String {
+        std::format!("SCORE x{}", self.multiplier)
+    }
+
+    fn symbol(&self) -> &'static str {
+        "X"
+    }
+
+    fn color(&self) -> Color {
+        config::power_ups::SCORE_MULTIPLIER_COLOR
+    }
+
+    fn modify(&self, modifiers: &mut Modifiers) {
+        modifiers.score_multiplier *= self.multiplier;
+    }
+}
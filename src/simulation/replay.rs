@@ -0,0 +1,333 @@
+use crate::tuning::Tuning;
+use crate::types::{difficulty::Difficulty, run_mode::RunMode};
+use super::sim_input::SimInput;
+
+const MAGIC: &[u8; 4] = b"SSRP";
+/// Version 2 added the difficulty preset, version 3 jump holds and
+/// fast-fall, and version 4 the stars collected. Version 5 marks the
+/// fairness re-rolls and the power-ups, which change the pickup draws, the
+/// score and which obstacles remain after a shield hit. Version 6 added the
+/// tuning hash, version 7 unlocks obstacle kinds on the score without
+/// pickups, version 8 ignores Down while fast-fall is turned off, and
+/// version 9 makes slow-motion scale the scroll speed. Each
+/// one came with new `World::step` rules, so an older file would
+/// re-simulate a different run and only the current version is read. Bump
+/// it whenever the rules change what a recorded input produces.
+const FORMAT_VERSION: u8 = 9;
+
+/// A finished run reduced to what is needed to re-simulate it: the course
+/// seed and layout, the gameplay tuning it was played under, plus the ticks
+/// on which the input changed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub run_mode: RunMode,
+    pub difficulty: Difficulty,
+    pub seed: u64,
+    pub spawn_x_position: f32,
+    pub floor_y_position: f32,
+    /// `Tuning::simulation_hash` of the run.
+    pub tuning_hash: u64,
+    pub total_ticks: u32,
+    pub final_score: u32,
+    pub final_stars: u32,
+    /// Ticks with a jump press, strictly increasing.
+    pub jump_ticks: Vec<u32>,
+    /// Ticks on which jump started or stopped being held, strictly
+    /// increasing. Jump is not held before the first one.
+    pub jump_hold_ticks: Vec<u32>,
+    /// Ticks on which Down started or stopped being held, like `jump_hold_ticks`.
+    pub fast_fall_ticks: Vec<u32>,
+}
+
+/// Collects the per-tick input of a run while it is being played.
+pub struct ReplayRecorder {
+    replay: Replay,
+    previous_input: SimInput,
+}
+
+impl ReplayRecorder {
+    pub fn new(
+        run_mode: RunMode,
+        difficulty: Difficulty,
+        seed: u64,
+        spawn_x_position: f32,
+        floor_y_position: f32,
+        tuning: &Tuning,
+    ) -> Self {
+        Self {
+            replay: Replay {
+                run_mode,
+                difficulty,
+                seed,
+                spawn_x_position,
+                floor_y_position,
+                tuning_hash: tuning.simulation_hash(),
+                total_ticks: 0,
+                final_score: 0,
+                final_stars: 0,
+                jump_ticks: Vec::new(),
+                jump_hold_ticks: Vec::new(),
+                fast_fall_ticks: Vec::new(),
+            },
+            previous_input: SimInput::default(),
+        }
+    }
+
+    pub fn record(&mut self, input: SimInput) {
+        let tick = self.replay.total_ticks;
+        if input.jump_pressed {
+            self.replay.jump_ticks.push(tick);
+        }
+        if input.jump_held != self.previous_input.jump_held {
+            self.replay.jump_hold_ticks.push(tick);
+        }
+        if input.fast_fall != self.previous_input.fast_fall {
+            self.replay.fast_fall_ticks.push(tick);
+        }
+        self.previous_input = input;
+        self.replay.total_ticks += 1;
+    }
+
+    pub fn finish(mut self, final_score: u32, final_stars: u32) -> Replay {
+        self.replay.final_score = final_score;
+        self.replay.final_stars = final_stars;
+        self.replay
+    }
+}
+
+impl Replay {
+    /// Input for the given tick, for feeding the replay back into a `World`.
+    pub fn input_at(&self, tick: u32) -> SimInput {
+        // An odd number of changes so far means the button is down.
+        let is_held = |changes: &[u32]| changes.partition_point(|&change| change <= tick) % 2 == 1;
+        SimInput {
+            jump_pressed: self.jump_ticks.binary_search(&tick).is_ok(),
+            jump_held: is_held(&self.jump_hold_ticks),
+            fast_fall: is_held(&self.fast_fall_ticks),
+        }
+    }
+
+    /// Refuses a replay recorded under other gameplay tuning, since
+    /// re-simulating it with `tuning` would give a different run.
+    pub fn check_tuning(&self, tuning: &Tuning) -> Result<(), String> {
+        if self.tuning_hash == tuning.simulation_hash() {
+            Ok(())
+        } else {
+            Err("replay was recorded with different gameplay tuning".to_string())
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(32 + self.jump_ticks.len() * 2);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        match self.run_mode {
+            RunMode::Endless => bytes.push(0),
+            RunMode::Daily { day } => {
+                bytes.push(1);
+                write_varint(&mut bytes, day);
+            }
+        }
+        bytes.push(self.difficulty.to_byte());
+        bytes.extend_from_slice(&self.spawn_x_position.to_le_bytes());
+        bytes.extend_from_slice(&self.floor_y_position.to_le_bytes());
+        bytes.extend_from_slice(&self.tuning_hash.to_le_bytes());
+        write_varint(&mut bytes, self.total_ticks as u64);
+        write_varint(&mut bytes, self.final_score as u64);
+        write_ticks(&mut bytes, &self.jump_ticks);
+        write_ticks(&mut bytes, &self.jump_hold_ticks);
+        write_ticks(&mut bytes, &self.fast_fall_ticks);
+        write_varint(&mut bytes, self.final_stars as u64);
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut reader = ByteReader { bytes, position: 0 };
+        if reader.take(4)? != MAGIC {
+            return Err("not a Soot Sprint replay".to_string());
+        }
+        let version = reader.take(1)?[0];
+        if version > FORMAT_VERSION {
+            return Err(std::format!("unsupported replay version {}", version));
+        }
+        if version < FORMAT_VERSION {
+            return Err(std::format!("replay was recorded by an older version of the game (format {})", version));
+        }
+        let seed = u64::from_le_bytes(reader.take_array()?);
+        let run_mode = match reader.take(1)?[0] {
+            0 => RunMode::Endless,
+            1 => RunMode::Daily { day: reader.varint()? },
+            other => return Err(std::format!("unknown run mode {}", other)),
+        };
+        let difficulty_byte = reader.take(1)?[0];
+        let difficulty = Difficulty::from_byte(difficulty_byte)
+            .ok_or_else(|| std::format!("unknown difficulty {}", difficulty_byte))?;
+        let spawn_x_position = f32::from_le_bytes(reader.take_array()?);
+        let floor_y_position = f32::from_le_bytes(reader.take_array()?);
+        let tuning_hash = u64::from_le_bytes(reader.take_array()?);
+        let total_ticks = reader.varint_u32()?;
+        let final_score = reader.varint_u32()?;
+        let jump_ticks = reader.ticks()?;
+        let jump_hold_ticks = reader.ticks()?;
+        let fast_fall_ticks = reader.ticks()?;
+        let final_stars = reader.varint_u32()?;
+        Ok(Self {
+            run_mode,
+            difficulty,
+            seed,
+            spawn_x_position,
+            floor_y_position,
+            tuning_hash,
+            total_ticks,
+            final_score,
+            final_stars,
+            jump_ticks,
+            jump_hold_ticks,
+            fast_fall_ticks,
+        })
+    }
+}
+
+/// Strictly increasing ticks as a count and the gaps between them.
+fn write_ticks(bytes: &mut Vec<u8>, ticks: &[u32]) {
+    write_varint(bytes, ticks.len() as u64);
+    let mut previous_tick = 0;
+    for &tick in ticks {
+        write_varint(bytes, (tick - previous_tick) as u64);
+        previous_tick = tick;
+    }
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.position + count;
+        let slice = self.bytes
+            .get(self.position..end)
+            .ok_or("replay file is truncated")?;
+        self.position = end;
+        Ok(slice)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.take(1)?[0];
+            value |= ((byte & 0x7F) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("malformed varint in replay file".to_string())
+    }
+
+    fn varint_u32(&mut self) -> Result<u32, String> {
+        u32::try_from(self.varint()?).map_err(|_| "value out of range in replay file".to_string())
+    }
+
+    fn ticks(&mut self) -> Result<Vec<u32>, String> {
+        let count = self.varint()? as usize;
+        let mut ticks = Vec::with_capacity(count.min(self.bytes.len()));
+        let mut tick = 0u32;
+        for _ in 0..count {
+            tick = tick
+                .checked_add(self.varint_u32()?)
+                .ok_or("tick overflow in replay file")?;
+            ticks.push(tick);
+        }
+        Ok(ticks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use crate::simulation::world::World;
+    use crate::tuning::Tuning;
+
+    #[test]
+    fn bytes_round_trip() {
+        let replay = Replay {
+            run_mode: RunMode::Daily { day: 20_000 },
+            difficulty: Difficulty::Hard,
+            seed: 0xDEAD_BEEF_1234,
+            spawn_x_position: 800.0,
+            floor_y_position: 390.0,
+            tuning_hash: 0x0123_4567_89AB_CDEF,
+            total_ticks: 5_000,
+            final_score: 833,
+            final_stars: 12,
+            jump_ticks: vec![3, 130, 131, 4_999],
+            jump_hold_ticks: vec![3, 20, 130],
+            fast_fall_ticks: vec![60, 75],
+        };
+        assert_eq!(Replay::from_bytes(&replay.to_bytes()), Ok(replay.clone()));
+        assert!(Replay::from_bytes(b"SSRP").is_err());
+
+        // Older files were played under other rules and are refused, not upgraded.
+        for version in 1..FORMAT_VERSION {
+            let mut older = replay.to_bytes();
+            older[4] = version;
+            assert!(Replay::from_bytes(&older).unwrap_err().contains("older version"));
+        }
+    }
+
+    #[test]
+    fn replaying_recorded_input_reproduces_the_run() {
+        let seed = 1234;
+        let tuning = Tuning::default();
+        let mut world = World::new(800.0, 390.0, seed, Difficulty::Easy, &tuning);
+        let mut recorder = ReplayRecorder::new(RunMode::Endless, Difficulty::Easy, seed, 800.0, 390.0, &tuning);
+        let mut tick = 0u32;
+        while !world.is_over {
+            let input = SimInput {
+                jump_pressed: tick % 97 < 2,
+                jump_held: tick % 97 < 8 + tick % 5,
+                fast_fall: tick % 131 > 120,
+            };
+            recorder.record(input);
+            world.step(input, config::FIXED_TIMESTEP);
+            tick += 1;
+        }
+        let replay = recorder.finish(world.score, world.stars_collected);
+        assert_eq!(replay.check_tuning(&tuning), Ok(()));
+        let mut retuned = tuning.clone();
+        retuned.difficulty.landing_recovery_s += 0.05;
+        assert!(replay.check_tuning(&retuned).is_err());
+
+        let mut replayed = World::new(
+            replay.spawn_x_position,
+            replay.floor_y_position,
+            replay.seed,
+            replay.difficulty,
+            &tuning,
+        );
+        for tick in 0..replay.total_ticks {
+            replayed.step(replay.input_at(tick), config::FIXED_TIMESTEP);
+        }
+        assert!(replayed.is_over);
+        assert_eq!(replayed.score, replay.final_score);
+        assert_eq!(replayed.stars_collected, replay.final_stars);
+        assert_eq!(replayed.distance, world.distance);
+    }
+}
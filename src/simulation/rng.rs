@@ -0,0 +1,65 @@
+/// Small PCG32 generator owned by a run. Unlike macroquad's global `rand`
+/// it can be seeded per run and cloned, so a seed always reproduces the
+/// same obstacle sequence.
+#[derive(Clone, Debug)]
+pub struct SeededRng {
+    state: u64,
+}
+
+const MULTIPLIER: u64 = 6364136223846793005;
+const INCREMENT: u64 = 1442695040888963407;
+
+impl SeededRng {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self { state: 0 };
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old_state = self.state;
+        self.state = old_state.wrapping_mul(MULTIPLIER).wrapping_add(INCREMENT);
+        let xorshifted = (((old_state >> 18) ^ old_state) >> 27) as u32;
+        let rotation = (old_state >> 59) as u32;
+        xorshifted.rotate_right(rotation)
+    }
+
+    /// Uniform float in `[low, high)`.
+    pub fn gen_range_f32(&mut self, low: f32, high: f32) -> f32 {
+        let unit = self.next_u32() as f64 / (u32::MAX as f64 + 1.0);
+        low + ((high - low) as f64 * unit) as f32
+    }
+
+    /// Index picked with probability proportional to its weight, or `None`
+    /// when no weight is above zero.
+    pub fn choose_weighted(&mut self, weights: &[f32]) -> Option<usize> {
+        let total: f32 = weights.iter().filter(|&&weight| weight > 0.0).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = self.gen_range_f32(0.0, total);
+        let last_positive = weights.iter().rposition(|&weight| weight > 0.0)?;
+        for (index, &weight) in weights.iter().enumerate() {
+            if weight <= 0.0 {
+                continue;
+            }
+            if roll < weight {
+                return Some(index);
+            }
+            roll -= weight;
+        }
+        // Float rounding can leave a sliver past the last weight.
+        Some(last_positive)
+    }
+}
+
+/// SplitMix64 finaliser, used to turn small, related values such as day
+/// numbers into well spread seeds.
+pub fn mix_seed(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,16 @@
+use crate::types::{power_up::PowerUpKind, score_type::ScoreType};
+
+/// Things that happened during a `World::step`, for the stage to react to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SimEvent {
+    Jumped,
+    Scored(u32),
+    MilestoneReached(ScoreType),
+    StarCollected,
+    PowerUpCollected(PowerUpKind),
+    /// An effect ran out or was used up.
+    PowerUpEnded(PowerUpKind),
+    /// A shield took a hit that would have ended the run.
+    CollisionAbsorbed,
+    Collided,
+}
@@ -0,0 +1,10 @@
+/// Everything the simulation needs to know about the player's input for one step.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SimInput {
+    /// Jump went down since the previous step.
+    pub jump_pressed: bool,
+    /// Jump is still down. Letting go while rising cuts the jump short.
+    pub jump_held: bool,
+    /// Down is held, which drops the player faster while airborne.
+    pub fast_fall: bool,
+}
@@ -0,0 +1,912 @@
+use macroquad::prelude::*;
+use crate::config;
+use crate::tuning::Tuning;
+use crate::types::{
+    difficulty::{Difficulty, DifficultySample},
+    obstacle::Obstacle,
+    obstacle_kind::{HitboxShape, Placement},
+    power_up::{PowerUpKind, PowerUpPickup},
+    score_type::ScoreType,
+    star::Star,
+};
+use super::collision::{Circle, Shape};
+use super::fairness;
+use super::power_ups::{self, ActivePowerUp, Modifiers};
+use super::rng::{mix_seed, SeededRng};
+use super::sim_event::SimEvent;
+use super::sim_input::SimInput;
+
+/// The whole state of a run, free of rendering and input polling so it
+/// can be stepped from a stage or from a test without a window.
+pub struct World {
+    pub player_y_position: f32,
+    pub previous_player_y_position: f32,
+    pub player_velocity_y: f32,
+    pub is_on_floor: bool,
+    /// Time left for a jump press made in the air to fire on landing.
+    pub jump_buffer_timer: f32,
+    /// Time left to jump after leaving the floor without jumping.
+    pub coyote_timer: f32,
+    /// Jumps made since leaving the floor, counted against `Modifiers::air_jumps`.
+    pub air_jumps_used: u32,
+    pub obstacles: Vec<Obstacle>,
+    /// Pixels left to scroll before the next obstacle spawns.
+    pub next_spawn_distance: f32,
+    /// Patterns still to come in the current combo.
+    pub combo_remaining: u32,
+    /// Spawns held back because no jump timing could clear them.
+    pub rejected_spawns: u32,
+    pub stars: Vec<Star>,
+    pub stars_collected: u32,
+    pub power_up_pickups: Vec<PowerUpPickup>,
+    /// Effects running now, at most one of each kind.
+    pub power_ups: Vec<ActivePowerUp>,
+    pub scroll_speed: f32,
+    pub score: u32,
+    /// Points from running alone, without star bonuses or the score
+    /// multiplier. Obstacle kinds unlock on it, so collecting pickups does
+    /// not change which kinds a seed's course can use.
+    pub base_score: u32,
+    pub score_timer: f32,
+    pub distance: f32,
+    /// `distance` before the last step, for drawing between steps.
+    pub previous_distance: f32,
+    pub floor_y_position: f32,
+    pub spawn_x_position: f32,
+    pub is_over: bool,
+    pub difficulty: Difficulty,
+    rng: SeededRng,
+    /// Separate from `rng` so rolling for stars and power-ups does not
+    /// shift the obstacle rolls. Pickups can still change the course: the
+    /// obstacles a shield removes no longer count in the fairness check.
+    pickup_rng: SeededRng,
+    tuning: Tuning,
+}
+
+impl World {
+    /// Copies `tuning` so the run keeps the values it started with.
+    pub fn new(
+        spawn_x_position: f32,
+        floor_y_position: f32,
+        seed: u64,
+        difficulty: Difficulty,
+        tuning: &Tuning,
+    ) -> Self {
+        Self {
+            player_y_position: 100.0,
+            previous_player_y_position: 100.0,
+            player_velocity_y: 0.0,
+            is_on_floor: false,
+            jump_buffer_timer: 0.0,
+            coyote_timer: 0.0,
+            air_jumps_used: 0,
+            obstacles: Vec::new(),
+            next_spawn_distance: tuning.obstacles.first_spawn_distance,
+            combo_remaining: 0,
+            rejected_spawns: 0,
+            stars: Vec::new(),
+            stars_collected: 0,
+            power_up_pickups: Vec::new(),
+            power_ups: Vec::new(),
+            scroll_speed: tuning.difficulty.curve(difficulty).sample(0.0).scroll_speed,
+            score: 0,
+            base_score: 0,
+            score_timer: 0.0,
+            distance: 0.0,
+            previous_distance: 0.0,
+            floor_y_position,
+            spawn_x_position,
+            is_over: false,
+            difficulty,
+            rng: SeededRng::new(seed),
+            pickup_rng: SeededRng::new(mix_seed(seed)),
+            tuning: tuning.clone(),
+        }
+    }
+
+    pub fn tuning(&self) -> &Tuning {
+        &self.tuning
+    }
+
+    /// Swaps the tuning mid-run. Only for dev hot reload, since a replay
+    /// of the run can no longer be reproduced afterwards.
+    pub fn set_tuning(&mut self, tuning: &Tuning) {
+        self.tuning = tuning.clone();
+    }
+
+    /// The run as changed by every active power-up.
+    pub fn modifiers(&self) -> Modifiers {
+        let mut modifiers = Modifiers::default();
+        for power_up in &self.power_ups {
+            power_up.effect.modify(&mut modifiers);
+        }
+        modifiers
+    }
+
+    /// Starts the effect of `kind`, or restarts its timer if it is running.
+    pub fn activate_power_up(&mut self, kind: PowerUpKind) {
+        let power_up = power_ups::create(kind, &self.tuning.power_ups);
+        match self.power_ups.iter_mut().find(|active| active.kind() == kind) {
+            Some(active) => *active = power_up,
+            None => self.power_ups.push(power_up),
+        }
+    }
+
+    pub fn speed_tier(&self) -> usize {
+        ScoreType::speed_tier(self.score)
+    }
+
+    /// Shortest spawn gap at `scroll_speed` that still lets the player clear
+    /// the widest obstacle, land, recover and jump again.
+    pub fn safe_spawn_gap(&self, scroll_speed: f32) -> f32 {
+        let player = &self.tuning.player;
+        let airtime = 2.0 * -player.jump_force / player.gravity;
+        let widest_obstacle = self.tuning.obstacles.kinds
+            .iter()
+            .map(|kind| kind.size[0])
+            .fold(0.0, f32::max);
+        scroll_speed * (airtime + self.tuning.difficulty.landing_recovery_s) + widest_obstacle
+    }
+
+    pub fn player_hitbox(&self) -> Circle {
+        self.player_hitbox_at(self.player_y_position)
+    }
+
+    /// The player's hitbox with the feet at `y_position`.
+    pub fn player_hitbox_at(&self, y_position: f32) -> Circle {
+        let player = &self.tuning.player;
+        Circle {
+            center: Vec2::new(player.x_position, y_position - player.hitbox_center_height),
+            radius: player.hitbox_radius,
+        }
+    }
+
+    /// Whether `candidates` can join the obstacles the player may still be
+    /// jumping over and leave a course some jump timing clears at
+    /// `course_speed`, and at its slowed speed when slow-motion can spawn,
+    /// since either may be running when the player gets there.
+    fn is_fair_spawn(&self, candidates: &[Obstacle], course_speed: f32) -> bool {
+        let mut speeds = vec![course_speed];
+        if self.tuning.power_ups.kinds.contains(&PowerUpKind::SlowMotion) {
+            speeds.push(self.slowed_speed(course_speed, self.tuning.power_ups.slow_motion_speed_scale));
+        }
+        speeds.iter().all(|&speed| self.is_fair_spawn_at(candidates, speed))
+    }
+
+    fn is_fair_spawn_at(&self, candidates: &[Obstacle], scroll_speed: f32) -> bool {
+        let player = &self.tuning.player;
+        let jump_length = scroll_speed * 2.0 * -player.jump_force / player.gravity;
+        let mut window: Vec<Obstacle> = self.obstacles
+            .iter()
+            .filter(|obstacle| obstacle.rect.right() + jump_length > player.x_position - player.hitbox_radius)
+            .chain(candidates)
+            .cloned()
+            .collect();
+        // Start the check from the floor with the first obstacle just
+        // arriving, so the player's current jump does not matter.
+        let shift = self.spawn_x_position - window[0].rect.x;
+        for obstacle in &mut window {
+            obstacle.rect.x += shift;
+        }
+        // The shift and the speed still rising put the obstacles up to a
+        // step's scroll away from where the check sees them on each step,
+        // so the player is checked that much wider.
+        let scroll_per_step = scroll_speed * config::FIXED_TIMESTEP;
+        fairness::is_clearable(self, &window, scroll_per_step, |_| scroll_per_step)
+    }
+
+    /// Collision shape of `obstacle` from its kind in the catalogue.
+    pub fn obstacle_hitbox(&self, obstacle: &Obstacle) -> Shape {
+        let rect = obstacle.rect;
+        // A hot reload can shrink the catalogue under obstacles already on
+        // screen; those keep their drawn rect.
+        let Some(kind) = self.tuning.obstacles.kinds.get(obstacle.kind) else {
+            return Shape::Rects(vec![rect]);
+        };
+        match &kind.shape {
+            HitboxShape::Box | HitboxShape::AlphaMask => {
+                let [width, height] = kind.hitbox_size();
+                Shape::Rects(vec![Rect::new(
+                    rect.x + kind.hitbox_offset[0],
+                    rect.y + kind.hitbox_offset[1],
+                    width,
+                    height,
+                )])
+            }
+            HitboxShape::Rects(rects) => Shape::Rects(
+                rects
+                    .iter()
+                    .map(|&[x, y, w, h]| Rect::new(rect.x + x, rect.y + y, w, h))
+                    .collect(),
+            ),
+            HitboxShape::Polygon(points) => Shape::Polygon(
+                points
+                    .iter()
+                    .map(|&[x, y]| Vec2::new(rect.x + x, rect.y + y))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// `speed` scaled by a slow-motion `scale`, but never below the curve's
+    /// starting speed, which every obstacle kind can be cleared at.
+    fn slowed_speed(&self, speed: f32, scale: f32) -> f32 {
+        let starting_speed = self.tuning.difficulty.curve(self.difficulty).sample(0.0).scroll_speed;
+        (speed * scale).max(starting_speed.min(speed))
+    }
+
+    /// Picks the next single obstacle or chunk and the gap after it. A pick
+    /// the fairness check rejects is dropped and retried a little further on.
+    /// Gaps and pickups are laid out for the curve's speed, so slow-motion
+    /// only changes how fast the course arrives.
+    fn spawn(&mut self, difficulty: &DifficultySample) {
+        let safe_gap = self.safe_spawn_gap(difficulty.scroll_speed);
+        let obstacle_tuning = &self.tuning.obstacles;
+        let kinds = &obstacle_tuning.kinds;
+        let kind_index = |name: &str| kinds.iter().position(|kind| kind.name == name);
+        let is_unlocked = |index: usize| self.base_score >= kinds[index].min_score;
+
+        // Each kind on its own, then every chunk within the budget, as
+        // kind indices with their distance from the pattern's start.
+        let mut patterns: Vec<(f32, Vec<(usize, f32)>)> = Vec::new();
+        if difficulty.chunk_budget >= 1.0 {
+            patterns.extend(kinds.iter().enumerate().map(|(index, kind)| {
+                let weight = if is_unlocked(index) {
+                    kind.spawn_weight * difficulty.mix_weight(&kind.name)
+                } else {
+                    0.0
+                };
+                (weight, vec![(index, 0.0)])
+            }));
+        }
+        for chunk in &obstacle_tuning.chunks {
+            if chunk.rating > difficulty.chunk_budget {
+                continue;
+            }
+            let Some(placed) = chunk.obstacles
+                .iter()
+                .map(|obstacle| kind_index(&obstacle.kind).map(|index| (index, obstacle.position(safe_gap))))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            let weight = if placed.iter().all(|&(index, _)| is_unlocked(index)) {
+                placed
+                    .iter()
+                    .map(|&(index, _)| difficulty.mix_weight(&kinds[index].name))
+                    .product::<f32>()
+                    * chunk.spawn_weight
+                    * difficulty.mix_weight(&chunk.name)
+            } else {
+                0.0
+            };
+            patterns.push((weight, placed));
+        }
+
+        let weights: Vec<f32> = patterns.iter().map(|(weight, _)| *weight).collect();
+        let mut pattern_length = 0.0;
+        if let Some(pattern_index) = self.rng.choose_weighted(&weights) {
+            let candidates: Vec<Obstacle> = patterns[pattern_index].1
+                .iter()
+                .map(|&(index, position)| {
+                    let kind = &kinds[index];
+                    Obstacle::new(
+                        self.spawn_x_position + position,
+                        kind.top_y(self.floor_y_position),
+                        kind.size[0],
+                        kind.size[1],
+                        index,
+                    )
+                })
+                .collect();
+            if !self.is_fair_spawn(&candidates, difficulty.scroll_speed) {
+                // Space the layout out and pick again a little later.
+                self.rejected_spawns += 1;
+                self.combo_remaining = 0;
+                self.next_spawn_distance = config::SPAWN_RETRY_DISTANCE;
+                return;
+            }
+            self.spawn_pickups(&candidates, difficulty.scroll_speed);
+            pattern_length = candidates
+                .iter()
+                .map(|obstacle| obstacle.rect.x - self.spawn_x_position)
+                .fold(0.0, f32::max);
+            self.obstacles.extend(candidates);
+        }
+
+        let max_combo_length = difficulty.combo_length.floor() as u32;
+        if self.combo_remaining == 0
+            && max_combo_length > 1
+            && self.rng.gen_range_f32(0.0, 1.0) < difficulty.combo_chance {
+            let extra_obstacles = self.rng.gen_range_f32(1.0, max_combo_length as f32) as u32;
+            self.combo_remaining = extra_obstacles.clamp(1, max_combo_length - 1);
+        }
+        // Gaps are distances from the pattern's last obstacle, so they
+        // stretch in time as the run speeds up, and never shrink below
+        // what a jump needs.
+        self.next_spawn_distance += pattern_length + if self.combo_remaining > 0 {
+            self.combo_remaining -= 1;
+            safe_gap
+        } else {
+            self.rng
+                .gen_range_f32(difficulty.gap_min, difficulty.gap_max)
+                .max(safe_gap)
+        };
+    }
+
+    /// Sometimes puts a power-up at the top of a full jump over the
+    /// pattern's first ground obstacle, or else lays a line of stars along
+    /// that jump, timed to peak above the obstacle's centre so a jump that
+    /// clears it collects them.
+    fn spawn_pickups(&mut self, candidates: &[Obstacle], scroll_speed: f32) {
+        let power_up_tuning = &self.tuning.power_ups;
+        let power_up_kind = if self.pickup_rng.gen_range_f32(0.0, 1.0) < power_up_tuning.spawn_chance {
+            let weights = vec![1.0; power_up_tuning.kinds.len()];
+            self.pickup_rng
+                .choose_weighted(&weights)
+                .map(|index| power_up_tuning.kinds[index])
+        } else {
+            None
+        };
+        let star_tuning = &self.tuning.stars;
+        if power_up_kind.is_none() && self.pickup_rng.gen_range_f32(0.0, 1.0) >= star_tuning.spawn_chance {
+            return;
+        }
+        let kinds = &self.tuning.obstacles.kinds;
+        let Some(obstacle) = candidates
+            .iter()
+            .find(|obstacle| kinds[obstacle.kind].placement == Placement::Ground)
+        else {
+            return;
+        };
+
+        let player = &self.tuning.player;
+        let arc = fairness::jump_arc(player, self.floor_y_position, config::FIXED_TIMESTEP);
+        let apex = arc
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(0, |(step, _)| step);
+        if let Some(kind) = power_up_kind {
+            let center = Vec2::new(obstacle.rect.center().x, arc[apex] - player.hitbox_center_height);
+            self.power_up_pickups.push(PowerUpPickup::new(kind, center));
+            return;
+        }
+        // The stars keep to the middle of the arc, clear of the ground.
+        let first = arc.len() / 5;
+        let last = arc.len() - 1 - first;
+        let count = star_tuning.count as usize;
+        let scroll_per_step = scroll_speed * config::FIXED_TIMESTEP;
+        for index in 0..count {
+            let step = if count == 1 {
+                apex
+            } else {
+                first + (last - first) * index / (count - 1)
+            };
+            let x_position = obstacle.rect.center().x + (step as f32 - apex as f32) * scroll_per_step;
+            let y_position = arc[step] - player.hitbox_center_height;
+            self.stars.push(Star::new(Vec2::new(x_position, y_position)));
+        }
+    }
+
+    /// Adds `points` to the score and reports every milestone passed.
+    fn add_score(&mut self, points: u32, events: &mut Vec<SimEvent>) {
+        let previous_score = self.score;
+        self.score += points;
+        events.push(SimEvent::Scored(self.score));
+        events.extend(
+            ScoreType::ALL
+                .into_iter()
+                .filter(|milestone| previous_score < milestone.value() && milestone.value() <= self.score)
+                .map(SimEvent::MilestoneReached),
+        );
+    }
+
+    /// Advances the run by `dt` seconds. Once a collision has been reported
+    /// the world is frozen and further steps do nothing.
+    pub fn step(&mut self, input: SimInput, dt: f32) -> Vec<SimEvent> {
+        let mut events = Vec::new();
+        if self.is_over {
+            return events;
+        }
+
+        self.previous_player_y_position = self.player_y_position;
+        self.previous_distance = self.distance;
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.previous_x = obstacle.rect.x;
+        }
+        for star in self.stars.iter_mut() {
+            star.previous_x = star.center.x;
+        }
+        for pickup in self.power_up_pickups.iter_mut() {
+            pickup.previous_x = pickup.center.x;
+        }
+
+        let modifiers = self.modifiers();
+        let player = &self.tuning.player;
+        let can_jump = self.is_on_floor || self.coyote_timer > 0.0;
+        let wants_jump = input.jump_pressed || self.jump_buffer_timer > 0.0;
+        let mut jumped = false;
+        if wants_jump && can_jump {
+            self.player_velocity_y = player.jump_force;
+            self.jump_buffer_timer = 0.0;
+            self.coyote_timer = 0.0;
+            jumped = true;
+            events.push(SimEvent::Jumped);
+        } else if input.jump_pressed && self.air_jumps_used < modifiers.air_jumps {
+            self.player_velocity_y = player.jump_force;
+            self.air_jumps_used += 1;
+            jumped = true;
+            events.push(SimEvent::Jumped);
+        } else if input.jump_pressed {
+            self.jump_buffer_timer = player.jump_buffer_s;
+        } else {
+            self.jump_buffer_timer = (self.jump_buffer_timer - dt).max(0.0);
+        }
+        if !input.jump_held && self.player_velocity_y < player.jump_release_velocity {
+            self.player_velocity_y = player.jump_release_velocity;
+        }
+        if input.fast_fall
+            && player.fast_fall_velocity > 0.0
+            && !jumped
+            && !self.is_on_floor
+            && self.player_velocity_y < player.fast_fall_velocity {
+            self.player_velocity_y = player.fast_fall_velocity;
+        }
+        self.player_velocity_y += player.gravity * dt;
+        self.player_y_position += self.player_velocity_y * dt;
+
+        let player_height = self.tuning.player.radius * 2.0;
+        if self.player_y_position < player_height {
+            self.player_y_position = player_height;
+            self.player_velocity_y = 0.0;
+        }
+
+        if self.player_y_position >= self.floor_y_position {
+            self.player_y_position = self.floor_y_position;
+            self.player_velocity_y = 0.0;
+            self.is_on_floor = true;
+            self.coyote_timer = self.tuning.player.coyote_time_s;
+            self.air_jumps_used = 0;
+        } else {
+            self.is_on_floor = false;
+            self.coyote_timer = (self.coyote_timer - dt).max(0.0);
+        }
+
+        self.score_timer += dt;
+        if self.score_timer >= config::SCORE_INTERVAL_S {
+            self.score_timer -= config::SCORE_INTERVAL_S;
+            self.base_score += 1;
+            self.add_score(modifiers.score_multiplier, &mut events);
+        }
+
+        for power_up in self.power_ups.iter_mut() {
+            power_up.remaining_s -= dt;
+        }
+        events.extend(
+            self.power_ups
+                .iter()
+                .filter(|power_up| power_up.remaining_s <= 0.0)
+                .map(|power_up| SimEvent::PowerUpEnded(power_up.kind())),
+        );
+        self.power_ups.retain(|power_up| power_up.remaining_s > 0.0);
+
+        let difficulty = self.tuning.difficulty.curve(self.difficulty).sample(self.distance);
+        self.scroll_speed = self.slowed_speed(difficulty.scroll_speed, modifiers.scroll_speed_scale);
+        let scrolled = self.scroll_speed * dt;
+        self.distance += scrolled;
+
+        self.next_spawn_distance -= scrolled;
+        if self.next_spawn_distance <= 0.0 {
+            self.spawn(&difficulty);
+        }
+
+        for obstacle in self.obstacles.iter_mut() {
+            obstacle.rect.x -= self.scroll_speed * dt;
+        }
+        self.obstacles.retain(|o| o.rect.x + o.rect.w > 0.0);
+
+        let player_hitbox = self.player_hitbox();
+        let star_radius = self.tuning.stars.radius;
+        for star in self.stars.iter_mut() {
+            star.center.x -= scrolled;
+            let to_player = player_hitbox.center - star.center;
+            let distance = to_player.length();
+            if distance < modifiers.magnet_radius {
+                star.center += to_player.clamp_length_max(modifiers.magnet_speed * dt);
+            }
+        }
+        self.stars.retain(|star| star.center.x + star_radius > 0.0);
+        let star_count = self.stars.len();
+        self.stars.retain(|star| {
+            !player_hitbox.overlaps_circle(&Circle { center: star.center, radius: star_radius })
+        });
+        let collected = (star_count - self.stars.len()) as u32;
+        if collected > 0 {
+            self.stars_collected += collected;
+            events.extend((0..collected).map(|_| SimEvent::StarCollected));
+            self.add_score(
+                collected * self.tuning.stars.score_bonus * modifiers.score_multiplier,
+                &mut events,
+            );
+        }
+
+        let pickup_radius = self.tuning.power_ups.radius;
+        for pickup in self.power_up_pickups.iter_mut() {
+            pickup.center.x -= scrolled;
+        }
+        self.power_up_pickups.retain(|pickup| pickup.center.x + pickup_radius > 0.0);
+        let mut picked_up = Vec::new();
+        self.power_up_pickups.retain(|pickup| {
+            let is_touched = player_hitbox.overlaps_circle(&Circle { center: pickup.center, radius: pickup_radius });
+            if is_touched {
+                picked_up.push(pickup.kind);
+            }
+            !is_touched
+        });
+        for kind in picked_up {
+            self.activate_power_up(kind);
+            events.push(SimEvent::PowerUpCollected(kind));
+        }
+
+        let hits: Vec<bool> = self.obstacles
+            .iter()
+            .map(|obstacle| player_hitbox.overlaps(&self.obstacle_hitbox(obstacle)))
+            .collect();
+        if hits.contains(&true) {
+            if let Some(shield) = self.power_ups.iter().position(|power_up| power_up.effect.absorbs_collision()) {
+                // The shield breaks the obstacles it stopped, so the player
+                // is not stuck inside them on the next step.
+                let kind = self.power_ups.remove(shield).kind();
+                let mut hits = hits.into_iter();
+                self.obstacles.retain(|_| !hits.next().unwrap_or(false));
+                events.push(SimEvent::PowerUpEnded(kind));
+                events.push(SimEvent::CollisionAbsorbed);
+            } else {
+                self.is_over = true;
+                events.push(SimEvent::Collided);
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::obstacle_chunk::ObstacleChunk;
+
+    const SCREEN_WIDTH: f32 = 800.0;
+    const FLOOR_Y: f32 = 390.0;
+    const SEED: u64 = 42;
+    const PRESS: SimInput = SimInput { jump_pressed: true, jump_held: true, fast_fall: false };
+    const HOLD: SimInput = SimInput { jump_pressed: false, jump_held: true, fast_fall: false };
+
+    fn landed_world() -> World {
+        let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, SEED, Difficulty::Normal, &Tuning::default());
+        while !world.is_on_floor {
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        }
+        world
+    }
+
+    /// Steps `input` until the player is back on the floor, returning the
+    /// steps taken and the highest point reached.
+    fn steps_until_landed(world: &mut World, input: SimInput) -> (u32, f32) {
+        let mut steps = 0;
+        let mut highest = world.player_y_position;
+        while !world.is_on_floor || steps == 0 {
+            world.step(input, config::FIXED_TIMESTEP);
+            highest = highest.min(world.player_y_position);
+            steps += 1;
+        }
+        (steps, highest)
+    }
+
+    fn run_until_over(world: &mut World, jump_every_tick: bool, max_ticks: u32) -> Vec<SimEvent> {
+        let mut all_events = Vec::new();
+        for _ in 0..max_ticks {
+            let input = if jump_every_tick { PRESS } else { SimInput::default() };
+            all_events.extend(world.step(input, config::FIXED_TIMESTEP));
+            if world.is_over {
+                break;
+            }
+        }
+        all_events
+    }
+
+    #[test]
+    fn idle_player_eventually_hits_a_ground_obstacle() {
+        let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, SEED, Difficulty::Normal, &Tuning::default());
+        let events = run_until_over(&mut world, false, 60 * 120);
+
+        assert!(world.is_over);
+        assert_eq!(events.last(), Some(&SimEvent::Collided));
+        assert!(!events.contains(&SimEvent::Jumped));
+    }
+
+    #[test]
+    fn jump_leaves_the_floor_and_lands_again() {
+        let mut world = landed_world();
+        let events = world.step(PRESS, config::FIXED_TIMESTEP);
+        assert!(events.contains(&SimEvent::Jumped));
+        assert!(!world.is_on_floor);
+
+        let (airborne_ticks, _) = steps_until_landed(&mut world, HOLD);
+        assert!(airborne_ticks > 10);
+        assert_eq!(world.player_y_position, FLOOR_Y);
+    }
+
+    #[test]
+    fn holding_jump_goes_higher_and_down_falls_faster() {
+        let mut world = landed_world();
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        let (held_steps, held_peak) = steps_until_landed(&mut world, HOLD);
+
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        let (tapped_steps, tapped_peak) = steps_until_landed(&mut world, SimInput::default());
+        assert!(tapped_peak > held_peak + 50.0);
+        assert!(tapped_steps < held_steps);
+
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        let fast_fall = SimInput { fast_fall: true, ..HOLD };
+        let (fast_fall_steps, _) = steps_until_landed(&mut world, fast_fall);
+        assert!(fast_fall_steps < held_steps);
+
+        // 0 turns fast-fall off rather than stopping the jump mid-air.
+        let mut tuning = world.tuning().clone();
+        tuning.player.fast_fall_velocity = 0.0;
+        world.set_tuning(&tuning);
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        let (fast_fall_off_steps, fast_fall_off_peak) = steps_until_landed(&mut world, fast_fall);
+        assert_eq!((fast_fall_off_steps, fast_fall_off_peak), (held_steps, held_peak));
+    }
+
+    #[test]
+    fn early_presses_are_buffered_and_late_ones_get_coyote_time() {
+        let mut world = landed_world();
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        while world.player_y_position < FLOOR_Y - 20.0 || world.player_velocity_y < 0.0 {
+            world.step(HOLD, config::FIXED_TIMESTEP);
+        }
+        // Pressed a few steps before touching down.
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        let mut events = Vec::new();
+        while events.is_empty() && world.player_velocity_y >= 0.0 {
+            events = world.step(HOLD, config::FIXED_TIMESTEP);
+        }
+        assert!(events.contains(&SimEvent::Jumped));
+
+        // Leave the floor without jumping, as if it dropped away.
+        let mut world = landed_world();
+        world.player_y_position -= 10.0;
+        world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        assert!(!world.is_on_floor);
+        assert!(world.step(PRESS, config::FIXED_TIMESTEP).contains(&SimEvent::Jumped));
+
+        let mut world = landed_world();
+        world.player_y_position -= 100.0;
+        for _ in 0..10 {
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        }
+        assert!(!world.is_on_floor);
+        assert!(!world.step(PRESS, config::FIXED_TIMESTEP).contains(&SimEvent::Jumped));
+    }
+
+    #[test]
+    fn score_advances_ten_points_per_second() {
+        let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, SEED, Difficulty::Normal, &Tuning::default());
+        for _ in 0..60 {
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        }
+        assert_eq!(world.score, 10);
+    }
+
+    #[test]
+    fn obstacle_kinds_wait_for_their_min_score() {
+        let mut tuning = Tuning::default();
+        tuning.obstacles.kinds[1].min_score = 1_000_000;
+        let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, SEED, Difficulty::Normal, &tuning);
+        // Bonus points do not count towards unlocks.
+        world.score = 2_000_000;
+        let mut spawned_kinds = Vec::new();
+        for _ in 0..60 * 60 {
+            world.step(PRESS, config::FIXED_TIMESTEP);
+            spawned_kinds.extend(world.obstacles.iter().map(|obstacle| obstacle.kind));
+            if world.is_over {
+                break;
+            }
+        }
+        assert!(!spawned_kinds.is_empty());
+        assert!(!spawned_kinds.contains(&1));
+    }
+
+    #[test]
+    fn same_seed_spawns_the_same_course() {
+        let spawned_course = |seed: u64| {
+            let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, seed, Difficulty::Normal, &Tuning::default());
+            let mut course = Vec::new();
+            while !world.is_over {
+                let previous_spawn_distance = world.next_spawn_distance;
+                world.step(SimInput::default(), config::FIXED_TIMESTEP);
+                if world.next_spawn_distance > previous_spawn_distance {
+                    let newest = world.obstacles.last().unwrap();
+                    course.push((world.score, newest.kind, world.next_spawn_distance.to_bits()));
+                }
+            }
+            course
+        };
+
+        assert!(!spawned_course(7).is_empty());
+        assert_eq!(spawned_course(7), spawned_course(7));
+        assert_ne!(spawned_course(7), spawned_course(8));
+    }
+
+    #[test]
+    fn chunks_spawn_as_a_unit_within_the_budget() {
+        let mut tuning = Tuning::default();
+        for kind in &mut tuning.obstacles.kinds {
+            kind.spawn_weight = 0.0;
+        }
+        tuning.obstacles.chunks = vec![
+            ObstacleChunk::new("pair", 1.0, &[("object_0", 0.0, 0.0), ("object_0", 1.0, 0.0)]),
+            ObstacleChunk::new("too_hard", 5.0, &[("object_2", 0.0, 0.0)]),
+        ];
+        let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, SEED, Difficulty::Normal, &tuning);
+        while world.obstacles.is_empty() {
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        }
+        let [first, second] = &world.obstacles[..] else {
+            panic!("expected the pair, got {} obstacles", world.obstacles.len());
+        };
+        let jump_gap = world.safe_spawn_gap(world.scroll_speed);
+        assert!((second.rect.x - first.rect.x - jump_gap).abs() < 0.01);
+
+        for _ in 0..60 * 30 {
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+            world.is_over = false;
+            assert!(world.obstacles.iter().all(|obstacle| obstacle.kind == 0));
+        }
+    }
+
+    #[test]
+    fn spawn_gaps_widen_with_speed_and_never_drop_below_a_jump() {
+        let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, SEED, Difficulty::Hard, &Tuning::default());
+        // Deep into the run, where the last keyframe's speed holds.
+        world.distance = 2_000_000.0;
+        let mut gaps = Vec::new();
+        while gaps.len() < 200 {
+            let previous_spawn_distance = world.next_spawn_distance;
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+            // Steps where the fairness check held a pick back spawn nothing.
+            if world.next_spawn_distance > previous_spawn_distance && !world.obstacles.is_empty() {
+                gaps.push(world.next_spawn_distance - previous_spawn_distance);
+            }
+            world.obstacles.clear();
+        }
+        assert_eq!(world.scroll_speed, 900.0);
+        let safe_gap = world.safe_spawn_gap(world.scroll_speed);
+        assert!(safe_gap > world.safe_spawn_gap(380.0));
+        // Each gap is measured after that step's scroll was taken off.
+        let scrolled_per_step = world.scroll_speed * config::FIXED_TIMESTEP;
+        assert!(gaps.iter().all(|&gap| gap >= safe_gap - scrolled_per_step - 0.01));
+    }
+
+    #[test]
+    fn a_jump_peaking_over_a_starred_obstacle_collects_every_star() {
+        let mut tuning = Tuning::default();
+        tuning.stars.spawn_chance = 1.0;
+        tuning.power_ups.spawn_chance = 0.0;
+        let mut world = World::new(SCREEN_WIDTH, FLOOR_Y, SEED, Difficulty::Normal, &tuning);
+        while !world.is_on_floor {
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        }
+        world.next_spawn_distance = f32::INFINITY;
+        let kind = &tuning.obstacles.kinds[0];
+        let obstacle = Obstacle::new(SCREEN_WIDTH, kind.top_y(FLOOR_Y), kind.size[0], kind.size[1], 0);
+        world.spawn_pickups(std::slice::from_ref(&obstacle), world.scroll_speed);
+        assert_eq!(world.stars.len(), tuning.stars.count as usize);
+
+        let arc = fairness::jump_arc(&tuning.player, FLOOR_Y, config::FIXED_TIMESTEP);
+        let apex = (0..arc.len()).min_by(|&a, &b| arc[a].total_cmp(&arc[b])).unwrap();
+        let scroll_per_step = world.scroll_speed * config::FIXED_TIMESTEP;
+        let steps_to_obstacle = (obstacle.rect.center().x - tuning.player.x_position) / scroll_per_step;
+        for _ in 1..steps_to_obstacle.round() as usize - apex {
+            world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        }
+        let score_before = world.score;
+        let mut events = world.step(PRESS, config::FIXED_TIMESTEP);
+        while !world.is_on_floor {
+            events.extend(world.step(HOLD, config::FIXED_TIMESTEP));
+        }
+
+        assert_eq!(world.stars_collected, tuning.stars.count);
+        assert!(world.stars.is_empty());
+        let collected_events = events.iter().filter(|&&event| event == SimEvent::StarCollected).count();
+        assert_eq!(collected_events, tuning.stars.count as usize);
+        assert!(world.score >= score_before + tuning.stars.count * tuning.stars.score_bonus);
+    }
+
+    #[test]
+    fn a_shield_takes_one_hit_and_effects_expire() {
+        let mut world = landed_world();
+        world.next_spawn_distance = f32::INFINITY;
+        let kind = &world.tuning().obstacles.kinds[0];
+        let x_position = world.tuning().player.x_position - kind.size[0] / 2.0;
+        let rock = Obstacle::new(x_position, kind.top_y(FLOOR_Y), kind.size[0], kind.size[1], 0);
+
+        world.activate_power_up(PowerUpKind::Shield);
+        world.obstacles.push(rock.clone());
+        let events = world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        assert!(events.contains(&SimEvent::CollisionAbsorbed));
+        assert!(!world.is_over);
+        assert!(world.obstacles.is_empty());
+        assert!(world.power_ups.is_empty());
+
+        world.obstacles.push(rock);
+        let events = world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        assert!(events.contains(&SimEvent::Collided));
+        assert!(world.is_over);
+
+        let mut world = landed_world();
+        world.next_spawn_distance = f32::INFINITY;
+        world.activate_power_up(PowerUpKind::ScoreMultiplier);
+        let duration_s = world.tuning().power_ups.score_multiplier_duration_s;
+        let score_before = world.score;
+        let mut events = Vec::new();
+        for _ in 0..(duration_s / config::FIXED_TIMESTEP).round() as u32 + 1 {
+            events.extend(world.step(SimInput::default(), config::FIXED_TIMESTEP));
+        }
+        assert!(events.contains(&SimEvent::PowerUpEnded(PowerUpKind::ScoreMultiplier)));
+        assert!(world.power_ups.is_empty());
+        assert_eq!(world.modifiers(), Modifiers::default());
+        let expected_points = (duration_s / config::SCORE_INTERVAL_S) as u32 * world.tuning().power_ups.score_multiplier;
+        assert!(world.score - score_before >= expected_points - world.tuning().power_ups.score_multiplier);
+    }
+
+    #[test]
+    fn double_jump_allows_one_jump_in_the_air() {
+        let mut world = landed_world();
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        world.step(HOLD, config::FIXED_TIMESTEP);
+        let events = world.step(PRESS, config::FIXED_TIMESTEP);
+        assert!(!events.contains(&SimEvent::Jumped));
+
+        let mut world = landed_world();
+        world.activate_power_up(PowerUpKind::DoubleJump);
+        world.step(PRESS, config::FIXED_TIMESTEP);
+        for _ in 0..20 {
+            world.step(HOLD, config::FIXED_TIMESTEP);
+        }
+        let falling_velocity = world.player_velocity_y;
+        let events = world.step(PRESS, config::FIXED_TIMESTEP);
+        assert!(events.contains(&SimEvent::Jumped));
+        assert!(world.player_velocity_y < falling_velocity);
+        world.step(HOLD, config::FIXED_TIMESTEP);
+        let events = world.step(PRESS, config::FIXED_TIMESTEP);
+        assert!(!events.contains(&SimEvent::Jumped));
+
+        steps_until_landed(&mut world, HOLD);
+        assert_eq!(world.air_jumps_used, 0);
+    }
+
+    #[test]
+    fn slow_motion_scales_the_scroll_speed_down_to_the_starting_speed() {
+        let mut world = landed_world();
+        world.next_spawn_distance = f32::INFINITY;
+        let starting_speed = world.tuning().difficulty.curve(Difficulty::Normal).sample(0.0).scroll_speed;
+        world.activate_power_up(PowerUpKind::SlowMotion);
+        world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        assert_eq!(world.scroll_speed, starting_speed);
+
+        // Past the curve's last keyframe, so the speed holds still.
+        world.power_ups.clear();
+        world.distance = 5_000_000.0;
+        world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        let full_speed = world.scroll_speed;
+        world.activate_power_up(PowerUpKind::SlowMotion);
+        world.step(SimInput::default(), config::FIXED_TIMESTEP);
+        assert_eq!(world.scroll_speed, full_speed * world.tuning().power_ups.slow_motion_speed_scale);
+    }
+}
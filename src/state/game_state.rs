@@ -1,6 +0,0 @@
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum GameState {
-    Intro,
-    Playing,
-    GameOver,
-}
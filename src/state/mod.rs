@@ -1 +1,3 @@
-pub mod game_state;
\ No newline at end of file
+pub mod replay_file;
+pub mod save_file;
+pub mod settings;
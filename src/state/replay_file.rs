@@ -0,0 +1,71 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use macroquad::prelude::warn;
+use crate::simulation::replay::Replay;
+use crate::tuning::Tuning;
+use crate::types::{difficulty::Difficulty, run_mode::RunMode};
+use super::save_file;
+
+const REPLAY_DIR_NAME: &str = "replays";
+const REPLAY_EXTENSION: &str = "ssr";
+
+/// Replay of the most recent run.
+pub const LAST_RUN: &str = "last";
+/// Replay of the best run on the most recent Daily Run course.
+pub const DAILY_BEST_RUN: &str = "daily_best";
+
+fn replay_path(name: &str) -> Option<PathBuf> {
+    save_file::data_dir().map(|dir| {
+        dir.join(REPLAY_DIR_NAME)
+            .join(name)
+            .with_extension(REPLAY_EXTENSION)
+    })
+}
+
+pub fn save(name: &str, replay: &Replay) {
+    let Some(path) = replay_path(name) else {
+        return;
+    };
+    if let Err(e) = save_file::write_atomically(&path, &replay.to_bytes()) {
+        warn!("Failed to write replay {}: {}", path.display(), e);
+    }
+}
+
+pub fn load(name: &str) -> Option<Replay> {
+    let path = replay_path(name)?;
+    if !path.exists() {
+        return None;
+    }
+    match load_path(&path) {
+        Ok(replay) => Some(replay),
+        Err(e) => {
+            warn!("Ignoring replay {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// The best recorded run on the same seeded course, if there is one and
+/// it was played under the same gameplay tuning. Only Daily Runs share a
+/// course between attempts, so Endless runs, each on a fresh course, never
+/// get a ghost.
+pub fn load_ghost(run_mode: RunMode, difficulty: Difficulty, tuning: &Tuning) -> Option<Replay> {
+    match run_mode {
+        RunMode::Endless => None,
+        RunMode::Daily { .. } => load(DAILY_BEST_RUN)
+            .filter(|replay| replay.run_mode == run_mode && replay.difficulty == difficulty)
+            .filter(|replay| match replay.check_tuning(tuning) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Ignoring the daily ghost: {}", e);
+                    false
+                }
+            }),
+    }
+}
+
+pub fn load_path(path: &Path) -> Result<Replay, String> {
+    let bytes = fs::read(path).map_err(|e: io::Error| e.to_string())?;
+    Replay::from_bytes(&bytes)
+}
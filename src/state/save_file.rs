@@ -0,0 +1,213 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use macroquad::prelude::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::game_stages::PersistentData;
+
+/// Bump whenever the on-disk layout of `PersistentData` changes and add a
+/// matching step to `migrate`.
+pub const SAVE_VERSION: u32 = 1;
+
+const APP_DIR_NAME: &str = "soot_sprint";
+const SAVE_FILE_NAME: &str = "save.json";
+
+#[derive(Serialize)]
+struct SaveFileOut<'a> {
+    version: u32,
+    data: &'a PersistentData,
+}
+
+#[derive(Deserialize)]
+struct SaveFileIn {
+    version: u32,
+    data: Value,
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_DIR_NAME))
+}
+
+fn save_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join(SAVE_FILE_NAME))
+}
+
+/// Why a save file could not be used.
+#[derive(Debug)]
+enum ParseError {
+    /// Written by a newer build, which an older one must leave alone.
+    Newer(u32),
+    Unusable(String),
+}
+
+/// Loads the save file, falling back to fresh data when it is missing or
+/// unreadable. Broken files are moved aside so the next save does not
+/// destroy them. A file written by a newer build is kept as it is, and
+/// the fresh data is marked read-only so this session does not replace it.
+pub fn load() -> PersistentData {
+    let Some(path) = save_path() else {
+        warn!("No data directory on this platform, progress will not be saved");
+        return PersistentData::default();
+    };
+    load_from(&path)
+}
+
+fn load_from(path: &Path) -> PersistentData {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return PersistentData::default(),
+        Err(e) => {
+            warn!("Could not read save file {}: {}", path.display(), e);
+            return PersistentData::default();
+        }
+    };
+
+    match parse(&contents) {
+        Ok(data) => data,
+        Err(ParseError::Newer(version)) => {
+            warn!(
+                "Save file {} is version {}, newer than supported version {}; progress will not be saved this session",
+                path.display(),
+                version,
+                SAVE_VERSION,
+            );
+            PersistentData {
+                is_read_only: true,
+                ..PersistentData::default()
+            }
+        }
+        Err(ParseError::Unusable(reason)) => {
+            let backup_path = path.with_extension("json.corrupt");
+            warn!(
+                "Save file {} is unusable ({}), backing it up to {}",
+                path.display(),
+                reason,
+                backup_path.display(),
+            );
+            if let Err(e) = fs::rename(path, &backup_path) {
+                warn!("Could not back up save file: {}", e);
+            }
+            PersistentData::default()
+        }
+    }
+}
+
+fn parse(contents: &str) -> Result<PersistentData, ParseError> {
+    let unusable = |e: serde_json::Error| ParseError::Unusable(e.to_string());
+    let save_file: SaveFileIn = serde_json::from_str(contents).map_err(unusable)?;
+    if save_file.version > SAVE_VERSION {
+        return Err(ParseError::Newer(save_file.version));
+    }
+    let data = migrate(save_file.data, save_file.version);
+    serde_json::from_value(data).map_err(unusable)
+}
+
+/// Upgrades older layouts one version at a time. Every layout so far is
+/// version 1, since fields added without a rename only need
+/// `#[serde(default)]` on `PersistentData`, so there is nothing to convert
+/// yet.
+fn migrate(data: Value, version: u32) -> Value {
+    if version < SAVE_VERSION {
+        info!("Migrating save file from version {} to {}", version, SAVE_VERSION);
+    }
+    data
+}
+
+/// Writes the save file atomically, unless it belongs to a newer build.
+pub fn save(data: &PersistentData) -> io::Result<()> {
+    let Some(path) = save_path() else {
+        return Ok(());
+    };
+    if data.is_read_only {
+        return Ok(());
+    }
+    let contents = serde_json::to_string_pretty(&SaveFileOut {
+        version: SAVE_VERSION,
+        data,
+    })?;
+
+    write_atomically(&path, contents.as_bytes())
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it over
+/// `path`, so a crash mid-write never leaves a half-written file behind.
+pub fn write_atomically(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temp_name = path.as_os_str().to_owned();
+    temp_name.push(".tmp");
+    let temp_path = PathBuf::from(temp_name);
+    {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_data_parses_back() {
+        let data = PersistentData {
+            high_score: 420,
+            total_stars: 17,
+            ..PersistentData::default()
+        };
+        let contents = serde_json::to_string(&SaveFileOut { version: SAVE_VERSION, data: &data }).unwrap();
+        let loaded = parse(&contents).unwrap();
+        assert_eq!((loaded.high_score, loaded.total_stars), (420, 17));
+    }
+
+    #[test]
+    fn corrupt_and_newer_files_are_refused() {
+        assert!(parse("{\"version\": 1, \"data\": {\"high_sc").is_err());
+        assert!(parse(r#"{"version": 1, "data": {"high_score": "lots"}}"#).is_err());
+        assert!(parse(r#"{"high_score": 5}"#).is_err());
+
+        let newer = std::format!(r#"{{"version": {}, "data": {{}}}}"#, SAVE_VERSION + 1);
+        assert!(matches!(parse(&newer), Err(ParseError::Newer(version)) if version == SAVE_VERSION + 1));
+    }
+
+    #[test]
+    fn newer_files_are_kept_and_corrupt_ones_moved_aside() {
+        let dir = std::env::temp_dir().join(std::format!("soot_sprint_save_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SAVE_FILE_NAME);
+
+        let newer = std::format!(r#"{{"version": {}, "data": {{"high_score": 5}}}}"#, SAVE_VERSION + 1);
+        fs::write(&path, &newer).unwrap();
+        let loaded = load_from(&path);
+        assert!(loaded.is_read_only);
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+
+        fs::write(&path, "{\"version\": 1, \"da").unwrap();
+        assert!(!load_from(&path).is_read_only);
+        assert!(!path.exists());
+        assert!(path.with_extension("json.corrupt").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn files_from_the_first_release_fill_in_later_fields() {
+        let first_release = r#"{
+            "version": 1,
+            "data": {
+                "high_score": 99,
+                "total_runs": 3,
+                "total_distance": 12345.0,
+                "best_per_speed_tier": [99, 0, 0, 0, 0, 0],
+                "last_played": 1700000000
+            }
+        }"#;
+        let loaded = parse(first_release).unwrap();
+        assert_eq!((loaded.high_score, loaded.total_runs), (99, 3));
+        assert_eq!(loaded.total_stars, 0);
+        assert!(loaded.daily_best.is_none());
+    }
+}
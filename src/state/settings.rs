@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+use crate::input::bindings::Bindings;
+use crate::types::difficulty::Difficulty;
+
+/// Player preferences, saved together with `PersistentData`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub audio: AudioSettings,
+    /// Preset for Endless runs, picked on the intro screen.
+    pub difficulty: Difficulty,
+    /// Keys, mouse buttons and touch bound to each action.
+    pub controls: Bindings,
+}
+
+/// Volumes in `[0, 1]`. Music and effects are scaled by the master volume.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 0.8,
+            music_volume: 0.6,
+            sfx_volume: 0.8,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub fn music_level(&self) -> f32 {
+        (self.master_volume * self.music_volume).clamp(0.0, 1.0)
+    }
+
+    pub fn sfx_level(&self) -> f32 {
+        (self.master_volume * self.sfx_volume).clamp(0.0, 1.0)
+    }
+}
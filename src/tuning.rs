@@ -0,0 +1,924 @@
+use std::fmt;
+use std::path::Path;
+use macroquad::logging::warn;
+use macroquad::texture::Image;
+use serde::Deserialize;
+use crate::config;
+use crate::simulation::collision;
+use crate::types::{
+    background::{BackgroundLayer, GroundStrip, LayerAnchor},
+    difficulty::{Difficulty, DifficultyCurve, DifficultyKeyframe},
+    obstacle_chunk::ObstacleChunk,
+    obstacle_kind::{HitboxShape, ObstacleKind, Placement},
+    particle_emitter::{ParticleEffect, ParticleEmitter},
+    power_up::PowerUpKind,
+};
+
+/// Designer-facing tuning file, read once at startup.
+pub const TUNING_PATH: &str = "assets/tuning.toml";
+
+/// Gameplay and UI values that used to be compiled in. Every field has a
+/// default matching the shipped game, so the file only needs the values
+/// that differ.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tuning {
+    pub player: PlayerTuning,
+    pub obstacles: ObstacleTuning,
+    pub difficulty: DifficultyTuning,
+    pub stars: StarTuning,
+    pub power_ups: PowerUpTuning,
+    pub background: BackgroundTuning,
+    pub particles: ParticleTuning,
+    pub intro: IntroTuning,
+    pub playing_ui: PlayingUiTuning,
+    pub game_over_ui: GameOverUiTuning,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerTuning {
+    pub x_position: f32,
+    /// Pixels per second squared.
+    pub gravity: f32,
+    /// Upward take-off velocity in pixels per second, so negative.
+    pub jump_force: f32,
+    /// Upward velocity a rising jump is cut to when jump is let go, for
+    /// hold-to-jump-higher. Equal to `jump_force` turns the cut off.
+    pub jump_release_velocity: f32,
+    /// How early a jump press still counts before landing.
+    pub jump_buffer_s: f32,
+    /// How long after leaving the floor without jumping a jump still works.
+    pub coyote_time_s: f32,
+    /// Downward velocity Down snaps to in the air, even while rising. 0
+    /// turns fast-fall off, leaving the jump untouched.
+    pub fast_fall_velocity: f32,
+    /// Half the drawn height of the sprite.
+    pub radius: f32,
+    /// Collision circle around the fuzzy body, leaving out the spikes and legs.
+    pub hitbox_radius: f32,
+    /// Height of the collision circle's centre above the feet.
+    pub hitbox_center_height: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObstacleTuning {
+    /// Pixels run before the first obstacle spawns.
+    pub first_spawn_distance: f32,
+    /// The catalogue spawns are drawn from, weighted by `spawn_weight`.
+    pub kinds: Vec<ObstacleKind>,
+    /// Authored patterns that spawn alongside the single kinds.
+    pub chunks: Vec<ObstacleChunk>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DifficultyTuning {
+    /// Time on the ground between two jumps that the spawn gaps always
+    /// leave room for, on top of the jump itself.
+    pub landing_recovery_s: f32,
+    pub easy: DifficultyCurve,
+    pub normal: DifficultyCurve,
+    pub hard: DifficultyCurve,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StarTuning {
+    /// Chance in `[0, 1]` that a spawn with a ground obstacle gets a line
+    /// of stars along the jump over it.
+    pub spawn_chance: f32,
+    /// Stars in each line.
+    pub count: u32,
+    /// Pickup radius around each star's centre.
+    pub radius: f32,
+    /// Drawn width and height.
+    pub size: f32,
+    /// Points added to the score per star.
+    pub score_bonus: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerUpTuning {
+    /// Chance in `[0, 1]` that a spawn with a ground obstacle gets a
+    /// power-up at the top of the jump over it, instead of stars.
+    pub spawn_chance: f32,
+    /// Kinds that can spawn, picked evenly.
+    pub kinds: Vec<PowerUpKind>,
+    /// Pickup radius around the centre, and drawn size.
+    pub radius: f32,
+    pub size: f32,
+    /// Seconds each effect lasts.
+    pub shield_duration_s: f32,
+    pub double_jump_duration_s: f32,
+    pub slow_motion_duration_s: f32,
+    pub magnet_duration_s: f32,
+    pub score_multiplier_duration_s: f32,
+    /// Jumps allowed in the air while double jump is active.
+    pub air_jumps: u32,
+    /// Multiplies the scroll speed during slow-motion, in `(0, 1]`. It
+    /// never slows the run below the difficulty curve's starting speed.
+    pub slow_motion_speed_scale: f32,
+    /// Stars within this distance fly to the player at `magnet_speed`.
+    pub magnet_radius: f32,
+    pub magnet_speed: f32,
+    pub score_multiplier: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackgroundTuning {
+    /// Parallax layers, drawn back to front.
+    pub layers: Vec<BackgroundLayer>,
+    pub ground: GroundStrip,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ParticleTuning {
+    /// Live particles a stage keeps at most. Spawns past it are dropped.
+    pub max_particles: usize,
+    pub landing_dust: ParticleEmitter,
+    pub soot_trail: ParticleEmitter,
+    pub collision_burst: ParticleEmitter,
+    pub milestone_sparkles: ParticleEmitter,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct IntroTuning {
+    pub slide_duration_s: f32,
+    pub button_width: f32,
+    pub button_height: f32,
+    pub button_spacing: f32,
+    pub font_size: f32,
+    pub line_height_factor: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayingUiTuning {
+    pub score_font_size: f32,
+    pub score_margin: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameOverUiTuning {
+    pub font_size: f32,
+    pub score_font_size: f32,
+}
+
+impl Default for PlayerTuning {
+    fn default() -> Self {
+        Self {
+            x_position: 75.0,
+            // Tuned as 1.5 and -25.0 per 60 Hz tick.
+            gravity: 5400.0,
+            jump_force: -1500.0,
+            jump_release_velocity: -500.0,
+            jump_buffer_s: 0.1,
+            coyote_time_s: 0.08,
+            fast_fall_velocity: 1800.0,
+            radius: 40.0,
+            hitbox_radius: 28.0,
+            hitbox_center_height: 50.0,
+        }
+    }
+}
+
+impl Default for ObstacleTuning {
+    fn default() -> Self {
+        let kind = |name: &str, size: [f32; 2], placement: Placement, elevation: f32| ObstacleKind {
+            name: name.to_string(),
+            texture: std::format!("assets/{}.png", name),
+            size,
+            hitbox_size: None,
+            hitbox_offset: [0.0, 0.0],
+            shape: HitboxShape::AlphaMask,
+            placement,
+            elevation,
+            spawn_weight: 1.0,
+            min_score: 0,
+            is_traced: false,
+        };
+        Self {
+            first_spawn_distance: 600.0,
+            kinds: vec![
+                kind("object_0", [80.0, 60.0], Placement::Ground, 0.0),
+                // Clears a ground obstacle's height plus 30 pixels.
+                kind("object_1", [70.0, 100.0], Placement::Floating, 90.0),
+                kind("object_2", [80.0, 60.0], Placement::Ground, 0.0),
+            ],
+            chunks: vec![
+                ObstacleChunk::new("long_beam", 1.5, &[("object_1", 0.0, 0.0), ("object_1", 0.0, 70.0)]),
+                ObstacleChunk::new("double_rock", 2.0, &[("object_0", 0.0, 0.0), ("object_2", 0.0, 70.0)]),
+                // Jump the rock, then stay down under the beam.
+                ObstacleChunk::new("low_high", 2.0, &[("object_0", 0.0, 0.0), ("object_1", 1.0, 0.0)]),
+                ObstacleChunk::new(
+                    "gap_then_triple",
+                    3.0,
+                    &[("object_2", 1.0, 0.0), ("object_0", 2.0, 0.0), ("object_2", 3.0, 0.0)],
+                ),
+            ],
+        }
+    }
+}
+
+impl Default for DifficultyTuning {
+    fn default() -> Self {
+        let curve = |keyframes| DifficultyCurve { keyframes };
+        let keyframe = DifficultyKeyframe::new;
+        Self {
+            landing_recovery_s: 0.15,
+            easy: curve(vec![
+                keyframe(0.0, 300.0, [500.0, 1000.0], 0.0, 1.0).with_mix("object_1", 0.0),
+                keyframe(30_000.0, 340.0, [550.0, 1100.0], 0.0, 1.0).with_chunk_budget(1.5),
+                keyframe(200_000.0, 450.0, [650.0, 1250.0], 0.1, 2.0).with_chunk_budget(2.0),
+                keyframe(800_000.0, 600.0, [800.0, 1500.0], 0.15, 2.0).with_chunk_budget(2.0),
+            ]),
+            // Follows the old score tiers: 300 px/s rising by 100 at each
+            // milestone, with the old 1.5 to 3 second spawn timer.
+            normal: curve(vec![
+                keyframe(0.0, 300.0, [450.0, 900.0], 0.0, 1.0),
+                keyframe(30_000.0, 400.0, [520.0, 1100.0], 0.1, 2.0).with_chunk_budget(2.0),
+                keyframe(190_000.0, 500.0, [600.0, 1250.0], 0.2, 2.0).with_chunk_budget(2.5),
+                keyframe(440_000.0, 600.0, [700.0, 1400.0], 0.25, 3.0).with_chunk_budget(3.0),
+                keyframe(1_000_000.0, 700.0, [800.0, 1600.0], 0.3, 3.0).with_chunk_budget(3.0),
+                keyframe(2_500_000.0, 800.0, [900.0, 1800.0], 0.35, 3.0).with_chunk_budget(3.0),
+            ]),
+            hard: curve(vec![
+                keyframe(0.0, 380.0, [420.0, 800.0], 0.15, 2.0).with_chunk_budget(2.0),
+                keyframe(40_000.0, 500.0, [500.0, 950.0], 0.25, 3.0).with_chunk_budget(3.0),
+                keyframe(250_000.0, 650.0, [600.0, 1150.0], 0.35, 3.0).with_chunk_budget(3.0),
+                keyframe(1_000_000.0, 900.0, [750.0, 1400.0], 0.45, 4.0).with_chunk_budget(3.0),
+            ]),
+        }
+    }
+}
+
+impl DifficultyTuning {
+    pub fn curve(&self, difficulty: Difficulty) -> &DifficultyCurve {
+        match difficulty {
+            Difficulty::Easy => &self.easy,
+            Difficulty::Normal => &self.normal,
+            Difficulty::Hard => &self.hard,
+        }
+    }
+}
+
+impl Default for StarTuning {
+    fn default() -> Self {
+        Self {
+            spawn_chance: 0.4,
+            count: 5,
+            radius: 16.0,
+            size: 36.0,
+            score_bonus: 10,
+        }
+    }
+}
+
+impl Default for PowerUpTuning {
+    fn default() -> Self {
+        Self {
+            spawn_chance: 0.1,
+            kinds: PowerUpKind::ALL.to_vec(),
+            radius: 22.0,
+            size: 44.0,
+            shield_duration_s: 15.0,
+            double_jump_duration_s: 10.0,
+            slow_motion_duration_s: 5.0,
+            magnet_duration_s: 10.0,
+            score_multiplier_duration_s: 10.0,
+            air_jumps: 1,
+            slow_motion_speed_scale: 0.6,
+            magnet_radius: 250.0,
+            magnet_speed: 900.0,
+            score_multiplier: 2,
+        }
+    }
+}
+
+impl Default for BackgroundTuning {
+    fn default() -> Self {
+        let layer = |texture: &str, scroll_factor, scale, anchor, offset_y, tint| BackgroundLayer {
+            texture: texture.to_string(),
+            scroll_factor,
+            scale,
+            tile: true,
+            anchor,
+            offset_y,
+            tint,
+        };
+        Self {
+            layers: vec![
+                layer("assets/background.png", 0.05, 0.15, LayerAnchor::Bottom, 0.0, [1.0, 1.0, 1.0, 1.0]),
+                layer("assets/hills.png", 0.2, 0.8, LayerAnchor::Floor, 0.0, [0.85, 0.86, 0.9, 0.8]),
+                layer("assets/hills.png", 0.45, 0.6, LayerAnchor::Floor, 10.0, [0.62, 0.63, 0.68, 1.0]),
+            ],
+            ground: GroundStrip {
+                texture: "assets/ground.png".to_string(),
+                scale: 1.0,
+                tint: [1.0, 1.0, 1.0, 1.0],
+                fill_color: [0.361, 0.329, 0.298, 1.0],
+            },
+        }
+    }
+}
+
+impl Default for ParticleTuning {
+    fn default() -> Self {
+        let emitter = |lifetime_s, speed, direction_deg, spread_deg, gravity, sizes: &[f32], colors: &[[f32; 4]]| {
+            ParticleEmitter {
+                burst: 0,
+                rate: 0.0,
+                lifetime_s,
+                speed,
+                direction_deg,
+                spread_deg,
+                gravity,
+                scroll_factor: 0.0,
+                offset: [0.0, 0.0],
+                spawn_radius: 0.0,
+                sizes: sizes.to_vec(),
+                colors: colors.to_vec(),
+            }
+        };
+        Self {
+            max_particles: 4000,
+            landing_dust: ParticleEmitter {
+                burst: 14,
+                scroll_factor: 1.0,
+                spawn_radius: 10.0,
+                ..emitter(
+                    [0.3, 0.6],
+                    [60.0, 180.0],
+                    90.0,
+                    150.0,
+                    300.0,
+                    &[6.0, 16.0],
+                    &[[0.55, 0.5, 0.45, 0.7], [0.6, 0.55, 0.5, 0.0]],
+                )
+            },
+            soot_trail: ParticleEmitter {
+                rate: 40.0,
+                scroll_factor: 0.8,
+                offset: [-20.0, -40.0],
+                spawn_radius: 6.0,
+                ..emitter(
+                    [0.4, 0.8],
+                    [10.0, 40.0],
+                    150.0,
+                    40.0,
+                    -60.0,
+                    &[5.0, 12.0],
+                    &[[0.1, 0.1, 0.1, 0.5], [0.2, 0.2, 0.2, 0.0]],
+                )
+            },
+            collision_burst: ParticleEmitter {
+                burst: 60,
+                ..emitter(
+                    [0.4, 0.9],
+                    [150.0, 450.0],
+                    90.0,
+                    360.0,
+                    600.0,
+                    &[10.0, 4.0],
+                    &[[0.1, 0.1, 0.1, 1.0], [0.3, 0.3, 0.3, 0.0]],
+                )
+            },
+            milestone_sparkles: ParticleEmitter {
+                burst: 40,
+                spawn_radius: 20.0,
+                ..emitter(
+                    [0.5, 1.0],
+                    [100.0, 300.0],
+                    90.0,
+                    360.0,
+                    150.0,
+                    &[3.0, 8.0, 2.0],
+                    &[[1.0, 0.95, 0.6, 1.0], [1.0, 0.8, 0.2, 1.0], [1.0, 0.6, 0.1, 0.0]],
+                )
+            },
+        }
+    }
+}
+
+impl ParticleTuning {
+    pub fn emitter(&self, effect: ParticleEffect) -> &ParticleEmitter {
+        match effect {
+            ParticleEffect::LandingDust => &self.landing_dust,
+            ParticleEffect::SootTrail => &self.soot_trail,
+            ParticleEffect::CollisionBurst => &self.collision_burst,
+            ParticleEffect::MilestoneSparkles => &self.milestone_sparkles,
+        }
+    }
+}
+
+impl Default for IntroTuning {
+    fn default() -> Self {
+        Self {
+            slide_duration_s: 8.0,
+            button_width: 200.0,
+            button_height: 50.0,
+            button_spacing: 15.0,
+            font_size: 30.0,
+            line_height_factor: 1.3,
+        }
+    }
+}
+
+impl Default for PlayingUiTuning {
+    fn default() -> Self {
+        Self {
+            score_font_size: 30.0,
+            score_margin: 20.0,
+        }
+    }
+}
+
+impl Default for GameOverUiTuning {
+    fn default() -> Self {
+        Self {
+            font_size: 60.0,
+            score_font_size: 30.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TuningError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    /// Every value that failed validation, not just the first.
+    Invalid(Vec<String>),
+}
+
+impl fmt::Display for TuningError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TuningError::Io(e) => write!(f, "could not read the file: {}", e),
+            TuningError::Parse(e) => write!(f, "{}", e),
+            TuningError::Invalid(problems) => write!(f, "{}", problems.join("; ")),
+        }
+    }
+}
+
+impl Tuning {
+    /// Fingerprint of every value `World::step` reads, with alpha masks
+    /// already traced. Replays store it, so one recorded under other
+    /// gameplay values is refused instead of re-simulating a different run.
+    /// Drawing and UI values are left out.
+    pub fn simulation_hash(&self) -> u64 {
+        const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0100_0000_01b3;
+        let values = std::format!(
+            "{:?}",
+            (&self.player, &self.obstacles, &self.difficulty, &self.stars, &self.power_ups),
+        );
+        values
+            .bytes()
+            .fold(FNV_OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(FNV_PRIME))
+    }
+
+    /// Reads and validates the tuning file. A missing file is not an error
+    /// and gives the built-in defaults.
+    pub fn load(path: &Path) -> Result<Self, TuningError> {
+        let mut tuning = match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                warn!("No tuning file at {}, using the defaults", path.display());
+                Self::default()
+            }
+            Err(e) => return Err(TuningError::Io(e)),
+        };
+        tuning.resolve_alpha_masks()?;
+        Ok(tuning)
+    }
+
+    /// Replaces every `AlphaMask` shape with rects traced from its texture,
+    /// scaled to the drawn size. The simulation never touches textures, so
+    /// the shapes have to be baked in before a `World` copies the tuning.
+    pub fn resolve_alpha_masks(&mut self) -> Result<(), TuningError> {
+        let mut problems = Vec::new();
+        for (index, kind) in self.obstacles.kinds.iter_mut().enumerate() {
+            if kind.shape != HitboxShape::AlphaMask {
+                continue;
+            }
+            let image = std::fs::read(&kind.texture)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| Image::from_file_with_format(&bytes, None).map_err(|e| e.to_string()));
+            match image {
+                Ok(image) => {
+                    let scale_x = kind.size[0] / image.width as f32;
+                    let scale_y = kind.size[1] / image.height as f32;
+                    let rects = collision::rects_from_alpha_mask(
+                        image.width as usize,
+                        image.height as usize,
+                        &image.bytes,
+                        config::ALPHA_MASK_BANDS,
+                    );
+                    kind.shape = HitboxShape::Rects(
+                        rects
+                            .into_iter()
+                            .map(|[x, y, w, h]| [x * scale_x, y * scale_y, w * scale_x, h * scale_y])
+                            .collect(),
+                    );
+                    kind.is_traced = true;
+                }
+                Err(e) => problems.push(std::format!(
+                    "obstacles.kinds[{}] ({}) alpha_mask could not read {}: {}",
+                    index,
+                    kind.name,
+                    kind.texture,
+                    e,
+                )),
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(TuningError::Invalid(problems))
+        }
+    }
+
+    /// Whether the hitboxes were traced from the image at `path`.
+    pub fn traces_texture(&self, path: &Path) -> bool {
+        self.obstacles.kinds.iter().any(|kind| kind.is_traced && Path::new(&kind.texture) == path)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, TuningError> {
+        let tuning: Tuning = toml::from_str(text).map_err(TuningError::Parse)?;
+        let problems = tuning.problems();
+        if problems.is_empty() {
+            Ok(tuning)
+        } else {
+            Err(TuningError::Invalid(problems))
+        }
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut require_positive = |name: &str, value: f32| {
+            if !(value > 0.0 && value.is_finite()) {
+                problems.push(std::format!("{} must be greater than 0, got {}", name, value));
+            }
+        };
+
+        require_positive("player.x_position", self.player.x_position);
+        require_positive("player.gravity", self.player.gravity);
+        require_positive("player.radius", self.player.radius);
+        require_positive("player.hitbox_radius", self.player.hitbox_radius);
+        require_positive("player.hitbox_center_height", self.player.hitbox_center_height);
+        require_positive("obstacles.first_spawn_distance", self.obstacles.first_spawn_distance);
+        require_positive("stars.radius", self.stars.radius);
+        require_positive("stars.size", self.stars.size);
+        require_positive("power_ups.radius", self.power_ups.radius);
+        require_positive("power_ups.size", self.power_ups.size);
+        require_positive("power_ups.shield_duration_s", self.power_ups.shield_duration_s);
+        require_positive("power_ups.double_jump_duration_s", self.power_ups.double_jump_duration_s);
+        require_positive("power_ups.slow_motion_duration_s", self.power_ups.slow_motion_duration_s);
+        require_positive("power_ups.magnet_duration_s", self.power_ups.magnet_duration_s);
+        require_positive("power_ups.score_multiplier_duration_s", self.power_ups.score_multiplier_duration_s);
+        require_positive("power_ups.slow_motion_speed_scale", self.power_ups.slow_motion_speed_scale);
+        require_positive("background.ground.scale", self.background.ground.scale);
+        require_positive("intro.slide_duration_s", self.intro.slide_duration_s);
+        require_positive("intro.button_width", self.intro.button_width);
+        require_positive("intro.button_height", self.intro.button_height);
+        require_positive("intro.font_size", self.intro.font_size);
+        require_positive("intro.line_height_factor", self.intro.line_height_factor);
+        require_positive("playing_ui.score_font_size", self.playing_ui.score_font_size);
+        require_positive("game_over_ui.font_size", self.game_over_ui.font_size);
+        require_positive("game_over_ui.score_font_size", self.game_over_ui.score_font_size);
+
+        if self.player.jump_force.is_nan() || self.player.jump_force >= 0.0 {
+            problems.push(std::format!(
+                "player.jump_force must be negative (upwards), got {}",
+                self.player.jump_force,
+            ));
+        }
+        if !(self.player.jump_release_velocity >= self.player.jump_force
+            && self.player.jump_release_velocity <= 0.0) {
+            problems.push(std::format!(
+                "player.jump_release_velocity must be between jump_force and 0, got {}",
+                self.player.jump_release_velocity,
+            ));
+        }
+        for (name, value) in [
+            ("player.jump_buffer_s", self.player.jump_buffer_s),
+            ("player.coyote_time_s", self.player.coyote_time_s),
+            ("player.fast_fall_velocity", self.player.fast_fall_velocity),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                problems.push(std::format!("{} must not be negative, got {}", name, value));
+            }
+        }
+        if self.difficulty.landing_recovery_s.is_nan() || self.difficulty.landing_recovery_s < 0.0 {
+            problems.push(std::format!(
+                "difficulty.landing_recovery_s must not be negative, got {}",
+                self.difficulty.landing_recovery_s,
+            ));
+        }
+        if !(self.stars.spawn_chance >= 0.0 && self.stars.spawn_chance <= 1.0) {
+            problems.push(std::format!(
+                "stars.spawn_chance must be between 0 and 1, got {}",
+                self.stars.spawn_chance,
+            ));
+        }
+        if self.stars.count == 0 {
+            problems.push("stars.count must be at least 1".to_string());
+        }
+        let power_ups = &self.power_ups;
+        if !(power_ups.spawn_chance >= 0.0 && power_ups.spawn_chance <= 1.0) {
+            problems.push(std::format!(
+                "power_ups.spawn_chance must be between 0 and 1, got {}",
+                power_ups.spawn_chance,
+            ));
+        }
+        if power_ups.slow_motion_speed_scale > 1.0 {
+            problems.push(std::format!(
+                "power_ups.slow_motion_speed_scale must be at most 1, got {}",
+                power_ups.slow_motion_speed_scale,
+            ));
+        }
+        if power_ups.score_multiplier == 0 {
+            problems.push("power_ups.score_multiplier must be at least 1".to_string());
+        }
+        for (name, value) in [
+            ("power_ups.magnet_radius", power_ups.magnet_radius),
+            ("power_ups.magnet_speed", power_ups.magnet_speed),
+        ] {
+            if !(value >= 0.0 && value.is_finite()) {
+                problems.push(std::format!("{} must not be negative, got {}", name, value));
+            }
+        }
+        problems.extend(self.obstacle_kind_problems());
+        problems.extend(self.obstacle_chunk_problems());
+        problems.extend(self.background_problems());
+        problems.extend(self.particle_problems());
+        for difficulty in Difficulty::ALL {
+            problems.extend(self.difficulty_curve_problems(difficulty));
+        }
+        problems
+    }
+
+    fn background_problems(&self) -> Vec<String> {
+        let ground = &self.background.ground;
+        let mut problems = Vec::new();
+        let is_color = |color: &[f32; 4]| color.iter().all(|channel| (0.0..=1.0).contains(channel));
+        for (index, layer) in self.background.layers.iter().enumerate() {
+            let name = std::format!("background.layers[{}]", index);
+            if layer.texture.is_empty() {
+                problems.push(std::format!("{} has an empty texture path", name));
+            }
+            if !(layer.scroll_factor >= 0.0 && layer.scroll_factor.is_finite()) {
+                problems.push(std::format!("{} scroll_factor must not be negative", name));
+            }
+            if !(layer.scale > 0.0 && layer.scale.is_finite()) {
+                problems.push(std::format!("{} scale must be greater than 0", name));
+            }
+            if !layer.offset_y.is_finite() {
+                problems.push(std::format!("{} offset_y must be a number", name));
+            }
+            if !is_color(&layer.tint) {
+                problems.push(std::format!("{} tint channels must be between 0 and 1", name));
+            }
+        }
+        if ground.texture.is_empty() {
+            problems.push("background.ground has an empty texture path".to_string());
+        }
+        if !is_color(&ground.tint) || !is_color(&ground.fill_color) {
+            problems.push("background.ground colour channels must be between 0 and 1".to_string());
+        }
+        problems
+    }
+
+    fn particle_problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.particles.max_particles == 0 {
+            problems.push("particles.max_particles must be at least 1".to_string());
+        }
+        for (name, emitter) in [
+            ("particles.landing_dust", &self.particles.landing_dust),
+            ("particles.soot_trail", &self.particles.soot_trail),
+            ("particles.collision_burst", &self.particles.collision_burst),
+            ("particles.milestone_sparkles", &self.particles.milestone_sparkles),
+        ] {
+            let [lifetime_min, lifetime_max] = emitter.lifetime_s;
+            if !(lifetime_min > 0.0 && lifetime_min <= lifetime_max && lifetime_max.is_finite()) {
+                problems.push(std::format!("{} needs 0 < lifetime_s[0] <= lifetime_s[1]", name));
+            }
+            let [speed_min, speed_max] = emitter.speed;
+            if !(speed_min >= 0.0 && speed_min <= speed_max && speed_max.is_finite()) {
+                problems.push(std::format!("{} needs 0 <= speed[0] <= speed[1]", name));
+            }
+            for (field, value) in [
+                ("rate", emitter.rate),
+                ("spread_deg", emitter.spread_deg),
+                ("scroll_factor", emitter.scroll_factor),
+                ("spawn_radius", emitter.spawn_radius),
+            ] {
+                if !(value >= 0.0 && value.is_finite()) {
+                    problems.push(std::format!("{} {} must not be negative", name, field));
+                }
+            }
+            if !(emitter.gravity.is_finite() && emitter.direction_deg.is_finite()) {
+                problems.push(std::format!("{} gravity and direction_deg must be numbers", name));
+            }
+            if emitter.sizes.is_empty() || emitter.sizes.iter().any(|size| !(*size >= 0.0 && size.is_finite())) {
+                problems.push(std::format!("{} needs at least one size, none negative", name));
+            }
+            let is_color = |color: &[f32; 4]| color.iter().all(|channel| (0.0..=1.0).contains(channel));
+            if emitter.colors.is_empty() || !emitter.colors.iter().all(is_color) {
+                problems.push(std::format!("{} needs at least one colour, channels between 0 and 1", name));
+            }
+        }
+        problems
+    }
+
+    fn difficulty_curve_problems(&self, difficulty: Difficulty) -> Vec<String> {
+        let curve = self.difficulty.curve(difficulty);
+        let curve_name = std::format!("difficulty.{}", difficulty.label().to_lowercase());
+        let mut problems = Vec::new();
+        match curve.keyframes.first() {
+            None => problems.push(std::format!("{} needs at least one keyframe", curve_name)),
+            Some(first) if first.distance != 0.0 => {
+                problems.push(std::format!("{} must start with a keyframe at distance 0", curve_name));
+            }
+            Some(_) => {}
+        }
+        for (index, keyframe) in curve.keyframes.iter().enumerate() {
+            let name = std::format!("{}.keyframes[{}]", curve_name, index);
+            if index > 0 && (keyframe.distance.is_nan() || keyframe.distance <= curve.keyframes[index - 1].distance) {
+                problems.push(std::format!("{} distance must be above the previous keyframe's", name));
+            }
+            if !(keyframe.scroll_speed > 0.0 && keyframe.scroll_speed.is_finite()) {
+                problems.push(std::format!("{} scroll_speed must be greater than 0", name));
+            }
+            if !(keyframe.gap_min > 0.0 && keyframe.gap_min <= keyframe.gap_max) {
+                problems.push(std::format!("{} needs 0 < gap_min <= gap_max", name));
+            }
+            if !(0.0..=1.0).contains(&keyframe.combo_chance) {
+                problems.push(std::format!("{} combo_chance must be between 0 and 1", name));
+            }
+            if keyframe.combo_length.is_nan() || keyframe.combo_length < 1.0 {
+                problems.push(std::format!("{} combo_length must be at least 1", name));
+            }
+            if keyframe.chunk_budget.is_nan() || keyframe.chunk_budget < 0.0 {
+                problems.push(std::format!("{} chunk_budget must not be negative", name));
+            }
+            for (mix_name, &weight) in &keyframe.mix {
+                let is_known = self.obstacles.kinds.iter().any(|kind| &kind.name == mix_name)
+                    || self.obstacles.chunks.iter().any(|chunk| &chunk.name == mix_name);
+                if !is_known {
+                    problems.push(std::format!("{} mixes unknown obstacle kind or chunk {}", name, mix_name));
+                }
+                if weight.is_nan() || weight < 0.0 {
+                    problems.push(std::format!("{} mix weight for {} must not be negative", name, mix_name));
+                }
+            }
+        }
+        problems
+    }
+
+    fn obstacle_chunk_problems(&self) -> Vec<String> {
+        let chunks = &self.obstacles.chunks;
+        let mut problems = Vec::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let name = std::format!("obstacles.chunks[{}] ({})", index, chunk.name);
+            if chunk.name.is_empty() {
+                problems.push(std::format!("obstacles.chunks[{}] has an empty name", index));
+            } else if chunks[..index].iter().any(|other| other.name == chunk.name)
+                || self.obstacles.kinds.iter().any(|kind| kind.name == chunk.name) {
+                problems.push(std::format!("{} has the same name as another chunk or kind", name));
+            }
+            if !(chunk.rating >= 0.0 && chunk.rating.is_finite()) {
+                problems.push(std::format!("{} rating must not be negative", name));
+            }
+            if chunk.spawn_weight.is_nan() || chunk.spawn_weight < 0.0 {
+                problems.push(std::format!("{} spawn_weight must not be negative", name));
+            }
+            if chunk.obstacles.is_empty() {
+                problems.push(std::format!("{} has no obstacles", name));
+            }
+            for obstacle in &chunk.obstacles {
+                if !self.obstacles.kinds.iter().any(|kind| kind.name == obstacle.kind) {
+                    problems.push(std::format!("{} uses unknown obstacle kind {}", name, obstacle.kind));
+                }
+                let is_placed = [obstacle.jumps, obstacle.offset]
+                    .iter()
+                    .all(|&value| value >= 0.0 && value.is_finite());
+                if !is_placed {
+                    problems.push(std::format!("{} {} needs jumps and offset of 0 or more", name, obstacle.kind));
+                }
+            }
+        }
+        problems
+    }
+
+    fn obstacle_kind_problems(&self) -> Vec<String> {
+        let kinds = &self.obstacles.kinds;
+        let mut problems = Vec::new();
+        if !kinds.iter().any(|kind| kind.spawn_weight > 0.0 && kind.min_score == 0) {
+            problems.push(
+                "obstacles.kinds needs a kind with min_score 0 and a spawn_weight above 0".to_string(),
+            );
+        }
+        for (index, kind) in kinds.iter().enumerate() {
+            let name = std::format!("obstacles.kinds[{}] ({})", index, kind.name);
+            if kind.name.is_empty() {
+                problems.push(std::format!("obstacles.kinds[{}] has an empty name", index));
+            } else if kinds[..index].iter().any(|other| other.name == kind.name) {
+                problems.push(std::format!("{} has the same name as an earlier kind", name));
+            }
+            if kind.texture.is_empty() {
+                problems.push(std::format!("{} has an empty texture path", name));
+            }
+            let [hitbox_width, hitbox_height] = kind.hitbox_size();
+            let sizes = [kind.size[0], kind.size[1], hitbox_width, hitbox_height];
+            if !sizes.iter().all(|&size| size > 0.0 && size.is_finite()) {
+                problems.push(std::format!("{} sizes must be greater than 0", name));
+            }
+            match &kind.shape {
+                HitboxShape::Rects(rects) if rects.is_empty() => {
+                    problems.push(std::format!("{} shape has no rects", name));
+                }
+                HitboxShape::Rects(rects) if rects.iter().any(|rect| !(rect[2] > 0.0 && rect[3] > 0.0)) => {
+                    problems.push(std::format!("{} shape rects need a width and height above 0", name));
+                }
+                HitboxShape::Polygon(points) if points.len() < 3 => {
+                    problems.push(std::format!("{} shape polygon needs at least 3 points", name));
+                }
+                _ => {}
+            }
+            if kind.elevation.is_nan() || kind.elevation < 0.0 {
+                problems.push(std::format!("{} elevation must not be negative", name));
+            }
+            if kind.spawn_weight.is_nan() || kind.spawn_weight < 0.0 {
+                problems.push(std::format!("{} spawn_weight must not be negative", name));
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_file_matches_the_defaults() {
+        let text = std::fs::read_to_string(TUNING_PATH).unwrap();
+        assert_eq!(Tuning::parse(&text).unwrap(), Tuning::default());
+    }
+
+    #[test]
+    fn the_simulation_hash_follows_gameplay_values_only() {
+        let tuning = Tuning::default();
+        let mut drawn_differently = tuning.clone();
+        drawn_differently.playing_ui.score_margin += 1.0;
+        drawn_differently.particles.max_particles += 1;
+        assert_eq!(drawn_differently.simulation_hash(), tuning.simulation_hash());
+
+        let mut heavier = tuning.clone();
+        heavier.player.gravity += 1.0;
+        assert_ne!(heavier.simulation_hash(), tuning.simulation_hash());
+    }
+
+    #[test]
+    fn alpha_masks_resolve_to_rects_inside_the_sprite() {
+        let tuning = Tuning::load(Path::new(TUNING_PATH)).unwrap();
+        for kind in &tuning.obstacles.kinds {
+            let HitboxShape::Rects(rects) = &kind.shape else {
+                panic!("{} was not resolved", kind.name);
+            };
+            assert!(tuning.traces_texture(Path::new(&kind.texture)));
+            assert!(!rects.is_empty());
+            for &[x, y, w, h] in rects {
+                assert!(x >= 0.0 && y >= 0.0);
+                assert!(x + w <= kind.size[0] + 0.01 && y + h <= kind.size[1] + 0.01);
+            }
+        }
+        assert!(!tuning.traces_texture(Path::new("assets/background.png")));
+    }
+
+    #[test]
+    fn missing_values_fall_back_and_bad_ones_are_all_reported() {
+        let tuning = Tuning::parse("[player]\ngravity = 6000.0\n").unwrap();
+        assert_eq!(tuning.player.gravity, 6000.0);
+        assert_eq!(tuning.player.jump_force, PlayerTuning::default().jump_force);
+
+        let Err(TuningError::Invalid(problems)) = Tuning::parse(
+            "[player]\njump_force = 10.0\n[obstacles]\nfirst_spawn_distance = -5.0\n\
+             [[obstacles.chunks]]\nname = \"pair\"\nrating = 1.0\nobstacles = [{ kind = \"boulder\" }]\n",
+        ) else {
+            panic!("expected validation errors");
+        };
+        assert_eq!(problems.len(), 4);
+
+        assert!(matches!(Tuning::parse("[player]\ngravty = 1.0\n"), Err(TuningError::Parse(_))));
+    }
+}
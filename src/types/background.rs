@@ -0,0 +1,63 @@
+use serde::Deserialize;
+
+/// The screen line a background layer is lined up against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LayerAnchor {
+    /// The layer's top edge at the top of the screen.
+    Top,
+    /// The layer's bottom edge on the floor line.
+    Floor,
+    /// The layer's bottom edge at the bottom of the screen.
+    Bottom,
+}
+
+/// One scrolling image behind the run, drawn back to front in file order.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BackgroundLayer {
+    pub texture: String,
+    /// Pixels the layer moves per pixel the run scrolls. 0 stays still,
+    /// 1 moves with the obstacles.
+    pub scroll_factor: f32,
+    /// Drawn size as a multiple of the texture's size.
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Repeats the image across the screen. An untiled layer is drawn once,
+    /// centred, and scrolls away.
+    #[serde(default = "default_tile")]
+    pub tile: bool,
+    pub anchor: LayerAnchor,
+    /// Pixels the layer sits below its anchor, negative to raise it.
+    #[serde(default)]
+    pub offset_y: f32,
+    /// Multiplied into the texture as `[r, g, b, a]` in `[0, 1]`.
+    #[serde(default = "default_tint")]
+    pub tint: [f32; 4],
+}
+
+/// The ground under the floor line, scrolling with the obstacles.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroundStrip {
+    /// Tiled along the top of the strip, its top edge on the floor line.
+    pub texture: String,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    #[serde(default = "default_tint")]
+    pub tint: [f32; 4],
+    /// Fills the rest of the strip below the texture, as `[r, g, b, a]`.
+    pub fill_color: [f32; 4],
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_tile() -> bool {
+    true
+}
+
+fn default_tint() -> [f32; 4] {
+    [1.0, 1.0, 1.0, 1.0]
+}
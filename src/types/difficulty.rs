@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+use serde::{Deserialize, Serialize};
+
+/// Preset picked on the intro screen. Each one has its own curve in the
+/// tuning file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Normal,
+            Difficulty::Normal => Difficulty::Hard,
+            Difficulty::Hard => Difficulty::Easy,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Difficulty::Easy => Difficulty::Hard,
+            Difficulty::Normal => Difficulty::Easy,
+            Difficulty::Hard => Difficulty::Normal,
+        }
+    }
+
+    pub fn to_byte(self) -> u8 {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+        }
+    }
+
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|difficulty| difficulty.to_byte() == byte)
+    }
+}
+
+/// Difficulty at one point of a run. Values between keyframes are blended
+/// linearly, and the last keyframe holds for the rest of the run.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DifficultyKeyframe {
+    /// Pixels travelled since the start of the run.
+    pub distance: f32,
+    /// Pixels per second.
+    pub scroll_speed: f32,
+    /// Pixels between one spawn and the next. The world widens gaps that
+    /// would be too short to land and jump again at the current speed.
+    pub gap_min: f32,
+    pub gap_max: f32,
+    /// Chance in `[0, 1]` that a spawn starts a combo of back-to-back
+    /// obstacles, each one jump apart.
+    #[serde(default)]
+    pub combo_chance: f32,
+    /// Most obstacles in a combo, rounded down.
+    #[serde(default = "default_combo_length")]
+    pub combo_length: f32,
+    /// Highest chunk `rating` that can spawn. Single obstacles rate 1.
+    #[serde(default = "default_chunk_budget")]
+    pub chunk_budget: f32,
+    /// Multipliers on `spawn_weight`, by kind or chunk name. A chunk is
+    /// also scaled by the multipliers of the kinds in it, and names left
+    /// out keep their weight.
+    #[serde(default)]
+    pub mix: BTreeMap<String, f32>,
+}
+
+fn default_combo_length() -> f32 {
+    1.0
+}
+
+fn default_chunk_budget() -> f32 {
+    1.0
+}
+
+/// Keyframes sorted by `distance`, the first one at distance 0.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DifficultyCurve {
+    pub keyframes: Vec<DifficultyKeyframe>,
+}
+
+/// A curve evaluated at one distance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DifficultySample {
+    pub scroll_speed: f32,
+    pub gap_min: f32,
+    pub gap_max: f32,
+    pub combo_chance: f32,
+    pub combo_length: f32,
+    pub chunk_budget: f32,
+    pub mix: BTreeMap<String, f32>,
+}
+
+impl DifficultyKeyframe {
+    pub fn new(
+        distance: f32,
+        scroll_speed: f32,
+        gap: [f32; 2],
+        combo_chance: f32,
+        combo_length: f32,
+    ) -> Self {
+        Self {
+            distance,
+            scroll_speed,
+            gap_min: gap[0],
+            gap_max: gap[1],
+            combo_chance,
+            combo_length,
+            chunk_budget: default_chunk_budget(),
+            mix: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_chunk_budget(mut self, chunk_budget: f32) -> Self {
+        self.chunk_budget = chunk_budget;
+        self
+    }
+
+    pub fn with_mix(mut self, kind_name: &str, weight: f32) -> Self {
+        self.mix.insert(kind_name.to_string(), weight);
+        self
+    }
+}
+
+impl DifficultyCurve {
+    pub fn sample(&self, distance: f32) -> DifficultySample {
+        let next_index = self.keyframes
+            .iter()
+            .position(|keyframe| keyframe.distance > distance)
+            .unwrap_or(self.keyframes.len());
+        let (from, to, t) = match next_index {
+            0 => (&self.keyframes[0], &self.keyframes[0], 0.0),
+            index if index == self.keyframes.len() => {
+                let last = &self.keyframes[index - 1];
+                (last, last, 0.0)
+            }
+            index => {
+                let from = &self.keyframes[index - 1];
+                let to = &self.keyframes[index];
+                (from, to, (distance - from.distance) / (to.distance - from.distance))
+            }
+        };
+
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        let mix = from.mix
+            .keys()
+            .chain(to.mix.keys())
+            .map(|name| {
+                let weight_from = from.mix.get(name).copied().unwrap_or(1.0);
+                let weight_to = to.mix.get(name).copied().unwrap_or(1.0);
+                (name.clone(), lerp(weight_from, weight_to))
+            })
+            .collect();
+        DifficultySample {
+            scroll_speed: lerp(from.scroll_speed, to.scroll_speed),
+            gap_min: lerp(from.gap_min, to.gap_min),
+            gap_max: lerp(from.gap_max, to.gap_max),
+            combo_chance: lerp(from.combo_chance, to.combo_chance),
+            combo_length: lerp(from.combo_length, to.combo_length),
+            chunk_budget: lerp(from.chunk_budget, to.chunk_budget),
+            mix,
+        }
+    }
+}
+
+impl DifficultySample {
+    pub fn mix_weight(&self, name: &str) -> f32 {
+        self.mix.get(name).copied().unwrap_or(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_blends_between_keyframes_and_holds_after_the_last() {
+        let curve = DifficultyCurve {
+            keyframes: vec![
+                DifficultyKeyframe::new(0.0, 300.0, [400.0, 800.0], 0.0, 1.0).with_mix("rock", 0.0),
+                DifficultyKeyframe::new(1000.0, 500.0, [600.0, 1000.0], 0.5, 3.0).with_chunk_budget(3.0),
+            ],
+        };
+        let halfway = curve.sample(500.0);
+        assert_eq!(halfway.scroll_speed, 400.0);
+        assert_eq!(halfway.gap_min, 500.0);
+        assert_eq!(halfway.combo_chance, 0.25);
+        assert_eq!(halfway.chunk_budget, 2.0);
+        assert_eq!(halfway.mix_weight("rock"), 0.5);
+        assert_eq!(halfway.mix_weight("bird"), 1.0);
+        assert_eq!(curve.sample(5000.0).scroll_speed, 500.0);
+    }
+}
@@ -1,3 +1,10 @@
-pub mod obstacle_type;
+pub mod background;
+pub mod difficulty;
+pub mod obstacle_chunk;
+pub mod obstacle_kind;
 pub mod score_type;
-pub mod obstacle;
\ No newline at end of file
+pub mod obstacle;
+pub mod particle_emitter;
+pub mod power_up;
+pub mod run_mode;
+pub mod star;
@@ -1,17 +1,19 @@
 use macroquad::math::Rect;
-use crate::types::obstacle_type::ObstacleType;
-
 
+#[derive(Clone, Debug)]
 pub struct Obstacle {
     pub rect: Rect,
-    pub kind: ObstacleType,
+    /// Index of the obstacle's kind in the tuning's catalogue.
+    pub kind: usize,
+    pub previous_x: f32,
 }
 
 impl Obstacle {
-    pub fn new(x: f32, y: f32, width: f32, height: f32, kind: ObstacleType) -> Self {
+    pub fn new(x: f32, y: f32, width: f32, height: f32, kind: usize) -> Self {
         Obstacle {
             rect: Rect::new(x, y, width, height),
             kind,
+            previous_x: x,
         }
     }
-}
\ No newline at end of file
+}
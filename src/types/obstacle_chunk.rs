@@ -0,0 +1,59 @@
+use serde::Deserialize;
+
+/// An authored run of obstacles that spawns as one unit, such as a double
+/// rock or a rock followed by a beam to run under. Every catalogue kind
+/// also spawns on its own as if it were a chunk of one with rating 1.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObstacleChunk {
+    pub name: String,
+    /// How hard the chunk is. It only spawns while the difficulty curve's
+    /// `chunk_budget` is at least this.
+    pub rating: f32,
+    #[serde(default = "default_spawn_weight")]
+    pub spawn_weight: f32,
+    pub obstacles: Vec<ChunkObstacle>,
+}
+
+/// One obstacle of a chunk, placed `jumps` safe jump gaps plus `offset`
+/// pixels after the chunk's start. Spacing in jumps keeps the pattern
+/// clearable as the run speeds up; pixels suit obstacles that touch.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChunkObstacle {
+    /// Name of a kind in the obstacle catalogue.
+    pub kind: String,
+    #[serde(default)]
+    pub jumps: f32,
+    #[serde(default)]
+    pub offset: f32,
+}
+
+fn default_spawn_weight() -> f32 {
+    1.0
+}
+
+impl ObstacleChunk {
+    pub fn new(name: &str, rating: f32, obstacles: &[(&str, f32, f32)]) -> Self {
+        Self {
+            name: name.to_string(),
+            rating,
+            spawn_weight: 1.0,
+            obstacles: obstacles
+                .iter()
+                .map(|&(kind, jumps, offset)| ChunkObstacle {
+                    kind: kind.to_string(),
+                    jumps,
+                    offset,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ChunkObstacle {
+    /// Distance from the chunk's start when one jump needs `jump_gap` pixels.
+    pub fn position(&self, jump_gap: f32) -> f32 {
+        self.jumps * jump_gap + self.offset
+    }
+}
@@ -0,0 +1,81 @@
+use serde::Deserialize;
+
+/// Where a kind of obstacle sits vertically when it spawns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Placement {
+    /// Standing on the floor line.
+    Ground,
+    /// Hovering `elevation` pixels above the floor line.
+    Floating,
+    /// Hanging from the top of the screen.
+    Ceiling,
+}
+
+/// How an obstacle's collision shape is described, in drawn pixels from the
+/// sprite's top-left corner.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HitboxShape {
+    /// A single box from `hitbox_size` and `hitbox_offset`.
+    #[default]
+    Box,
+    /// Inset rects as `[x, y, width, height]`.
+    Rects(Vec<[f32; 4]>),
+    /// Outline points as `[x, y]`.
+    Polygon(Vec<[f32; 2]>),
+    /// Rects generated from the texture's opaque pixels by `Tuning::load`.
+    /// Behaves like `Box` until then.
+    AlphaMask,
+}
+
+/// One entry of the obstacle catalogue in the tuning file. Obstacles refer
+/// to their kind by its index in the catalogue.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ObstacleKind {
+    pub name: String,
+    pub texture: String,
+    /// Drawn width and height.
+    pub size: [f32; 2],
+    /// Collision width and height, the drawn size when left out.
+    #[serde(default)]
+    pub hitbox_size: Option<[f32; 2]>,
+    /// Collision box position from the sprite's top-left corner.
+    #[serde(default)]
+    pub hitbox_offset: [f32; 2],
+    #[serde(default)]
+    pub shape: HitboxShape,
+    pub placement: Placement,
+    #[serde(default)]
+    pub elevation: f32,
+    #[serde(default = "default_spawn_weight")]
+    pub spawn_weight: f32,
+    /// Score from running alone, without star bonuses or the score
+    /// multiplier, the run needs before this kind can spawn.
+    #[serde(default)]
+    pub min_score: u32,
+    /// Set by `Tuning::load` when `shape` was traced from the texture, so
+    /// a changed texture means loading the tuning again.
+    #[serde(skip)]
+    pub is_traced: bool,
+}
+
+fn default_spawn_weight() -> f32 {
+    1.0
+}
+
+impl ObstacleKind {
+    pub fn hitbox_size(&self) -> [f32; 2] {
+        self.hitbox_size.unwrap_or(self.size)
+    }
+
+    /// Top edge of the sprite for a run whose floor is at `floor_y_position`.
+    pub fn top_y(&self, floor_y_position: f32) -> f32 {
+        match self.placement {
+            Placement::Ground => floor_y_position - self.size[1],
+            Placement::Floating => floor_y_position - self.elevation - self.size[1],
+            Placement::Ceiling => 0.0,
+        }
+    }
+}
@@ -1,6 +0,0 @@
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum ObstacleType {
-    Object0,
-    Object1,
-    Object2,
-}
\ No newline at end of file
@@ -0,0 +1,96 @@
+use serde::Deserialize;
+
+/// The effects a run plays, each with its own emitter in the tuning file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParticleEffect {
+    /// A puff at the feet when the player lands.
+    LandingDust,
+    /// Soot shed behind the player while running on the floor.
+    SootTrail,
+    /// Bits flying off a collision, absorbed or not.
+    CollisionBurst,
+    /// Sparkles around the player on a score milestone.
+    MilestoneSparkles,
+}
+
+impl ParticleEffect {
+    pub const COUNT: usize = 4;
+
+    pub fn index(self) -> usize {
+        match self {
+            ParticleEffect::LandingDust => 0,
+            ParticleEffect::SootTrail => 1,
+            ParticleEffect::CollisionBurst => 2,
+            ParticleEffect::MilestoneSparkles => 3,
+        }
+    }
+}
+
+/// How an effect's particles are spawned, move and change over their life.
+/// Curves are lists of values spread evenly from birth to death and blended
+/// in between, so a single value holds still.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ParticleEmitter {
+    /// Particles spawned at once when the effect is triggered.
+    #[serde(default)]
+    pub burst: u32,
+    /// Particles per second while the effect is running.
+    #[serde(default)]
+    pub rate: f32,
+    /// Lifetime range in seconds.
+    pub lifetime_s: [f32; 2],
+    /// Launch speed range in pixels per second.
+    pub speed: [f32; 2],
+    /// Launch direction in degrees, counter-clockwise from the right, so
+    /// 90 is straight up.
+    #[serde(default = "default_direction_deg")]
+    pub direction_deg: f32,
+    /// Full width of the cone launch directions are picked from, 360 for
+    /// every direction.
+    #[serde(default)]
+    pub spread_deg: f32,
+    /// Downward pull in pixels per second squared, negative to rise.
+    #[serde(default)]
+    pub gravity: f32,
+    /// How much of the run's scroll speed carries the particles left, 1 to
+    /// stay on the ground.
+    #[serde(default)]
+    pub scroll_factor: f32,
+    /// Spawn point from the effect's anchor on the player.
+    #[serde(default)]
+    pub offset: [f32; 2],
+    /// Particles spawn anywhere within this distance of the spawn point.
+    #[serde(default)]
+    pub spawn_radius: f32,
+    /// Drawn diameter over the particle's life.
+    pub sizes: Vec<f32>,
+    /// `[r, g, b, a]` in `[0, 1]` over the particle's life.
+    pub colors: Vec<[f32; 4]>,
+}
+
+fn default_direction_deg() -> f32 {
+    90.0
+}
+
+impl ParticleEmitter {
+    pub fn size_at(&self, life: f32) -> f32 {
+        sample_curve(&self.sizes, life, |from, to, t| from + (to - from) * t).unwrap_or(0.0)
+    }
+
+    pub fn color_at(&self, life: f32) -> [f32; 4] {
+        sample_curve(&self.colors, life, |from, to, t| {
+            std::array::from_fn(|channel| from[channel] + (to[channel] - from[channel]) * t)
+        })
+        .unwrap_or([0.0; 4])
+    }
+}
+
+/// Blends between the evenly spaced `values` at `life` in `[0, 1]`.
+fn sample_curve<T: Copy>(values: &[T], life: f32, lerp: impl Fn(T, T, f32) -> T) -> Option<T> {
+    let last = values.len().checked_sub(1)?;
+    let position = life.clamp(0.0, 1.0) * last as f32;
+    let index = (position as usize).min(last.saturating_sub(1));
+    let next = (index + 1).min(last);
+    Some(lerp(values[index], values[next], position - index as f32))
+}
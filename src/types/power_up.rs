@@ -0,0 +1,42 @@
+use macroquad::math::Vec2;
+use serde::Deserialize;
+
+/// The power-ups a pickup can hold. Each one maps to a `PowerUpEffect` in
+/// `simulation::power_ups`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerUpKind {
+    Shield,
+    DoubleJump,
+    SlowMotion,
+    Magnet,
+    ScoreMultiplier,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 5] = [
+        PowerUpKind::Shield,
+        PowerUpKind::DoubleJump,
+        PowerUpKind::SlowMotion,
+        PowerUpKind::Magnet,
+        PowerUpKind::ScoreMultiplier,
+    ];
+}
+
+/// A power-up waiting on the course, scrolling with the obstacles.
+#[derive(Clone, Debug)]
+pub struct PowerUpPickup {
+    pub kind: PowerUpKind,
+    pub center: Vec2,
+    pub previous_x: f32,
+}
+
+impl PowerUpPickup {
+    pub fn new(kind: PowerUpKind, center: Vec2) -> Self {
+        PowerUpPickup {
+            kind,
+            center,
+            previous_x: center.x,
+        }
+    }
+}
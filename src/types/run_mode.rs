@@ -0,0 +1,48 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::simulation::rng::mix_seed;
+use super::difficulty::Difficulty;
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Which course a run is played on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunMode {
+    /// A fresh random course every run.
+    Endless,
+    /// The same course for everyone on a given UTC day.
+    Daily { day: u64 },
+}
+
+impl RunMode {
+    pub fn daily_today() -> Self {
+        RunMode::Daily { day: current_day() }
+    }
+
+    /// Difficulty a run is played on. Daily Runs are always Normal so that
+    /// everyone races the same course.
+    pub fn difficulty(self, chosen: Difficulty) -> Difficulty {
+        match self {
+            RunMode::Endless => chosen,
+            RunMode::Daily { .. } => Difficulty::Normal,
+        }
+    }
+
+    pub fn seed(self) -> u64 {
+        match self {
+            RunMode::Endless => mix_seed(unix_time().as_nanos() as u64),
+            RunMode::Daily { day } => mix_seed(day),
+        }
+    }
+}
+
+fn unix_time() -> std::time::Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Days since the Unix epoch in UTC, so the daily course flips at the same
+/// moment for every player.
+pub fn current_day() -> u64 {
+    unix_time().as_secs() / SECONDS_PER_DAY
+}
@@ -1,4 +1,5 @@
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[allow(clippy::enum_variant_names)]
 pub enum ScoreType {
     FirstValue = 1000,
     SecondValue = 5000,
@@ -7,8 +8,24 @@ pub enum ScoreType {
     FifthValue = 50000,
 }
 
+/// The initial speed plus one tier per `ScoreType` threshold.
+pub const SPEED_TIER_COUNT: usize = ScoreType::ALL.len() + 1;
+
 impl ScoreType {
+    pub const ALL: [ScoreType; 5] = [
+        ScoreType::FirstValue,
+        ScoreType::SecondValue,
+        ScoreType::ThirdValue,
+        ScoreType::FourthValue,
+        ScoreType::FifthValue,
+    ];
+
     pub fn value(self) -> u32 {
         self as u32
     }
+
+    /// Number of thresholds the score has crossed, `0` before the first one.
+    pub fn speed_tier(score: u32) -> usize {
+        Self::ALL.iter().filter(|threshold| score >= threshold.value()).count()
+    }
 }
\ No newline at end of file
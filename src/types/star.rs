@@ -0,0 +1,17 @@
+use macroquad::math::Vec2;
+
+/// A collectible floating on a jump arc, scrolling with the obstacles.
+#[derive(Clone, Debug)]
+pub struct Star {
+    pub center: Vec2,
+    pub previous_x: f32,
+}
+
+impl Star {
+    pub fn new(center: Vec2) -> Self {
+        Star {
+            center,
+            previous_x: center.x,
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use macroquad::prelude::*;
+use crate::config::display::{LETTERBOX_COLOR, VIRTUAL_HEIGHT, VIRTUAL_WIDTH};
+
+/// Stages draw at a fixed virtual resolution into a render target, which is
+/// scaled to fit the window with bars filling the rest. Layout, spawn
+/// positions and the floor then stay put however the window is sized.
+pub struct VirtualScreen {
+    target: RenderTarget,
+    camera: Camera2D,
+}
+
+impl VirtualScreen {
+    pub fn new() -> Self {
+        let target = render_target(VIRTUAL_WIDTH as u32, VIRTUAL_HEIGHT as u32);
+        target.texture.set_filter(FilterMode::Linear);
+        let mut camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, VIRTUAL_WIDTH, VIRTUAL_HEIGHT));
+        camera.render_target = Some(target.clone());
+        Self { target, camera }
+    }
+
+    /// Sends the following draws to the virtual screen.
+    pub fn begin(&self) {
+        set_camera(&self.camera);
+    }
+
+    /// Draws the virtual screen into the window, letterboxed.
+    pub fn present(&self) {
+        set_default_camera();
+        clear_background(LETTERBOX_COLOR);
+        let area = letterbox(Vec2::new(screen_width(), screen_height()));
+        draw_texture_ex(
+            &self.target.texture,
+            area.x,
+            area.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(area.size()),
+                // Render targets are stored upside down.
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+
+    /// Maps a mouse or touch position in the window to the virtual screen.
+    pub fn to_virtual(&self, window_position: Vec2) -> Vec2 {
+        window_to_virtual(window_position, letterbox(Vec2::new(screen_width(), screen_height())))
+    }
+}
+
+/// The largest area with the virtual aspect ratio that fits in a window of
+/// `window_size`, centred.
+pub fn letterbox(window_size: Vec2) -> Rect {
+    let scale = (window_size.x / VIRTUAL_WIDTH).min(window_size.y / VIRTUAL_HEIGHT);
+    let size = Vec2::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT) * scale;
+    Rect::new(
+        (window_size.x - size.x) / 2.0,
+        (window_size.y - size.y) / 2.0,
+        size.x,
+        size.y,
+    )
+}
+
+/// Maps a window position into the virtual screen drawn at `area`. A
+/// position on the bars lands outside `0..VIRTUAL_WIDTH` or `0..VIRTUAL_HEIGHT`.
+pub fn window_to_virtual(window_position: Vec2, area: Rect) -> Vec2 {
+    (window_position - area.point()) * (VIRTUAL_WIDTH / area.w)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_and_tall_windows_are_letterboxed_and_map_back() {
+        let wide = letterbox(Vec2::new(VIRTUAL_WIDTH * 3.0, VIRTUAL_HEIGHT * 2.0));
+        assert_eq!(wide.size(), Vec2::new(VIRTUAL_WIDTH * 2.0, VIRTUAL_HEIGHT * 2.0));
+        assert_eq!(wide.point(), Vec2::new(VIRTUAL_WIDTH / 2.0, 0.0));
+
+        let tall = letterbox(Vec2::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT * 3.0));
+        assert_eq!(tall.point(), Vec2::new(0.0, VIRTUAL_HEIGHT));
+
+        let far_corner = wide.point() + wide.size();
+        assert_eq!(window_to_virtual(far_corner, wide), Vec2::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT));
+        assert_eq!(window_to_virtual(wide.center(), wide), Vec2::new(VIRTUAL_WIDTH, VIRTUAL_HEIGHT) / 2.0);
+        assert!(window_to_virtual(Vec2::ZERO, wide).x < 0.0);
+    }
+}